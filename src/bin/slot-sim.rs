@@ -0,0 +1,142 @@
+// Headless simulator for the slot math, e.g.
+// `cargo run --release --no-default-features --bin slot-sim -- --machine 5x3 --spins 10000000 --seed 42 --json sim.json`
+
+use std::fs::File;
+use std::io::Write;
+use rand::prelude::*;
+use slot_machine::core::simulation::{simulate, SimulationConfig};
+use slot_machine::core::config::parse_number;
+use slot_machine::core::validation::validate_or_exit;
+use slot_machine::util::json::{load_machine, save_machine};
+
+const USAGE: &str = "usage: slot-sim [--machine NAME|FILE] [--spins N] [--bet AMOUNT] [--seed N] [--json PATH] [--csv PATH] [--export-machine PATH]";
+
+/// Command line options for the simulator
+#[derive(Debug)]
+struct SimOptions {
+    machine: String,
+    spins: u64,
+    bet: f32,
+    seed: Option<u64>,
+    json: Option<String>, // where to write the JSON report, "-" for stdout
+    csv: Option<String>, // where to write the win histogram, "-" for stdout
+    export_machine: Option<String>, // write the machine definition here, a starting point for new machines
+}
+
+impl Default for SimOptions {
+    fn default() -> Self {
+        Self {
+            machine: "3x3".to_string(),
+            spins: 1_000_000,
+            bet: 1.0,
+            seed: None,
+            json: None,
+            csv: None,
+            export_machine: None,
+        }
+    }
+}
+
+impl SimOptions {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--machine" => options.machine = value()?,
+                "--spins" => options.spins = parse_number(&arg, &value()?)?,
+                "--bet" => {
+                    options.bet = parse_number(&arg, &value()?)?;
+                    if options.bet <= 0.0 {
+                        return Err("the bet must be more than 0".to_string());
+                    }
+                }
+                "--seed" => options.seed = Some(parse_number(&arg, &value()?)?),
+                "--json" => options.json = Some(value()?),
+                "--csv" => options.csv = Some(value()?),
+                "--export-machine" => options.export_machine = Some(value()?),
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+
+        // Both exports on stdout would run together into one unreadable stream
+        if options.json.as_deref() == Some("-") && options.csv.as_deref() == Some("-") {
+            return Err("--json and --csv can't both write to stdout".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn main() {
+    let options = match SimOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let machine = match load_machine(&options.machine) {
+        Ok(machine) => machine,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    validate_or_exit(&machine);
+
+    if let Some(path) = &options.export_machine {
+        if let Err(err) = save_machine(&machine, path) {
+            eprintln!("⚠️ Failed to write {}: {}", path, err);
+            std::process::exit(1);
+        }
+        eprintln!("💾 Machine definition written to {}", path);
+        return;
+    }
+
+    let config = SimulationConfig {
+        spins: options.spins,
+        bet: options.bet,
+        seed: options.seed.unwrap_or_else(|| thread_rng().next_u64()),
+    };
+    eprintln!("🎰 Simulating {} spins of {} ({} reels x {} rows)...",
+        config.spins, options.machine, machine.cols(), machine.rows());
+    let report = simulate(&machine, &config);
+
+    // Keep stdout clean for the exports when they're going there
+    if options.json.as_deref() == Some("-") || options.csv.as_deref() == Some("-") {
+        eprintln!("{}", report);
+    } else {
+        println!("{}", report);
+    }
+
+    if let Some(path) = &options.json {
+        let json = match serde_json::to_string_pretty(&report) {
+            Ok(json) => json,
+            Err(err) => {
+                eprintln!("⚠️ Failed to serialize the report: {}", err);
+                std::process::exit(1);
+            }
+        };
+        write_output(path, &json);
+    }
+    if let Some(path) = &options.csv {
+        write_output(path, &report.distribution.to_csv());
+    }
+}
+
+// Write an export to a file, or to stdout for "-"
+fn write_output(path: &str, contents: &str) {
+    let written = if path == "-" {
+        std::io::stdout().write_all(contents.as_bytes())
+    } else {
+        File::create(path).and_then(|mut file| file.write_all(contents.as_bytes()))
+    };
+    if let Err(err) = written {
+        eprintln!("⚠️ Failed to write {}: {}", path, err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,146 @@
+// Tunes a machine's symbol weights to a target RTP and writes the result as a machine file, e.g.
+// `cargo run --release --no-default-features --bin slot-solve -- --machine 3x3 --rtp 95 --hit-frequency 30 --out classic.json`
+// The game and slot-sim load the file with `--machine classic.json`
+
+use slot_machine::core::solver::{solve, SolverTargets, WeightBounds, DEFAULT_SPINS};
+use slot_machine::core::config::parse_number;
+use slot_machine::core::validation::validate_or_exit;
+use slot_machine::util::json::{load_machine, save_machine};
+
+const USAGE: &str = "usage: slot-solve --rtp PERCENT [--hit-frequency PERCENT] [--tolerance PERCENT] \
+[--machine NAME|FILE] [--min-weight W] [--max-weight W] [--bounds SYMBOL=MIN:MAX]... \
+[--per-reel] [--max-evaluations N] [--spins N] [--out PATH]";
+
+/// Command line options for the solver, percentages as typed (95 for 95%)
+#[derive(Debug)]
+struct SolveOptions {
+    machine: String,
+    rtp: Option<f64>,
+    hit_frequency: Option<f64>,
+    tolerance: f64,
+    bounds: WeightBounds,
+    symbol_bounds: Vec<(String, WeightBounds)>,
+    per_reel: bool,
+    max_evaluations: usize,
+    spins: u64,
+    out: String,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        Self {
+            machine: "3x3".to_string(),
+            rtp: None,
+            hit_frequency: None,
+            tolerance: 0.5,
+            bounds: WeightBounds { min: 0.1, max: 200.0 },
+            symbol_bounds: Vec::new(),
+            per_reel: false,
+            max_evaluations: 2000,
+            spins: DEFAULT_SPINS,
+            out: "machine.json".to_string(),
+        }
+    }
+}
+
+// "Wild=0.5:2" -> ("Wild", 0.5..=2)
+fn parse_bounds(value: &str) -> Result<(String, WeightBounds), String> {
+    let invalid = || format!("invalid bounds: {} (expected SYMBOL=MIN:MAX)", value);
+    let (name, range) = value.split_once('=').ok_or_else(invalid)?;
+    let (min, max) = range.split_once(':').ok_or_else(invalid)?;
+    let min = min.parse().map_err(|_| invalid())?;
+    let max = max.parse().map_err(|_| invalid())?;
+    Ok((name.to_string(), WeightBounds { min, max }))
+}
+
+impl SolveOptions {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--machine" => options.machine = value()?,
+                "--rtp" => options.rtp = Some(parse_number(&arg, &value()?)?),
+                "--hit-frequency" => options.hit_frequency = Some(parse_number(&arg, &value()?)?),
+                "--tolerance" => options.tolerance = parse_number(&arg, &value()?)?,
+                "--min-weight" => options.bounds.min = parse_number(&arg, &value()?)?,
+                "--max-weight" => options.bounds.max = parse_number(&arg, &value()?)?,
+                "--bounds" => options.symbol_bounds.push(parse_bounds(&value()?)?),
+                "--per-reel" => options.per_reel = true,
+                "--max-evaluations" => options.max_evaluations = parse_number(&arg, &value()?)?,
+                "--spins" => options.spins = parse_number(&arg, &value()?)?,
+                "--out" => options.out = value()?,
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+
+        if options.rtp.is_none() {
+            return Err("--rtp is required".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn main() {
+    let options = match SolveOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let machine = match load_machine(&options.machine) {
+        Ok(machine) => machine,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    validate_or_exit(&machine);
+
+    let targets = SolverTargets {
+        rtp: options.rtp.unwrap_or_default() / 100.0,
+        hit_frequency: options.hit_frequency.map(|percent| percent / 100.0),
+        tolerance: options.tolerance / 100.0,
+        bounds: options.bounds,
+        symbol_bounds: options.symbol_bounds,
+        per_reel: options.per_reel,
+        max_evaluations: options.max_evaluations,
+        spins: options.spins,
+    };
+    println!("🎯 Solving {} for {:.2}% RTP...", options.machine, targets.rtp * 100.0);
+    let result = match solve(&machine, &targets) {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+
+    println!("{:<12} {:>6} {:>10} {:>10}", "Symbol", "Reel", "Before", "After");
+    for weight in &result.weights {
+        let reel = weight.reel.map_or("all".to_string(), |reel| (reel + 1).to_string());
+        println!("{:<12} {:>6} {:>10.3} {:>10.3}", weight.name, reel, weight.before, weight.after);
+    }
+    println!("RTP:           {:.3}%", result.rtp * 100.0);
+    println!("Hit frequency: {:.3}%", result.hit_frequency * 100.0);
+    println!("Measured over {} spins per evaluation, {} evaluations", targets.spins, result.evaluations);
+
+    if let Err(err) = save_machine(&result.machine, &options.out) {
+        eprintln!("⚠️ Failed to write {}: {}", options.out, err);
+        std::process::exit(1);
+    }
+    println!("💾 Machine definition written to {}", options.out);
+
+    if !result.converged {
+        eprintln!("⚠️ Couldn't reach the targets within the weight bounds, the closest machine was written");
+        if !targets.per_reel {
+            eprintln!("   Try --per-reel, weighting every reel on its own reaches much lower returns");
+        }
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,161 @@
+// Estimates how far players get in the progression mode on a machine, e.g.
+// `cargo run --release --no-default-features --bin slot-survival -- --machine 5x3 --pool 100 --bet 5 --rolls 20 --goals exp:200:1.5`
+
+use std::fs::File;
+use std::io::Write;
+use rand::prelude::*;
+use slot_machine::core::survival::{analyze_survival, BetStrategy, GoalCurve, SurvivalConfig};
+use slot_machine::core::config::parse_number;
+use slot_machine::core::validation::validate_or_exit;
+use slot_machine::util::json::load_machine;
+
+const USAGE: &str = "usage: slot-survival [--machine NAME|FILE] [--pool AMOUNT] \
+[--bet AMOUNT | --bet-fraction F | --martingale BASE:MAX] [--rolls N] \
+[--goals linear:FIRST:STEP | exp:FIRST:GROWTH | list:GOAL,GOAL,...] [--levels N] [--runs N] [--seed N] [--json PATH]";
+
+/// Command line options for the survival analysis
+#[derive(Debug)]
+struct SurvivalOptions {
+    machine: String,
+    pool: f32,
+    bet: BetStrategy,
+    rolls: u32,
+    goals: GoalCurve,
+    levels: usize,
+    runs: u64,
+    seed: Option<u64>,
+    json: Option<String>, // where to write the JSON report, "-" for stdout
+}
+
+impl Default for SurvivalOptions {
+    // The game's starting pool and bet
+    fn default() -> Self {
+        Self {
+            machine: "3x3".to_string(),
+            pool: 100.0,
+            bet: BetStrategy::Flat(5.0),
+            rolls: 20,
+            goals: GoalCurve::Exponential { first: 200.0, growth: 1.5 },
+            levels: 10,
+            runs: 100_000,
+            seed: None,
+            json: None,
+        }
+    }
+}
+
+// "linear:200:100", "exp:200:1.5" or "list:150,300,600"
+fn parse_goals(value: &str) -> Result<GoalCurve, String> {
+    let invalid = || format!("invalid goals: {} (expected linear:FIRST:STEP, exp:FIRST:GROWTH or list:GOAL,...)", value);
+    let (kind, rest) = value.split_once(':').ok_or_else(invalid)?;
+    let numbers = |separator: char| -> Result<Vec<f32>, String> {
+        rest.split(separator).map(|number| number.trim().parse().map_err(|_| invalid())).collect()
+    };
+    match kind {
+        "linear" => match numbers(':')?[..] {
+            [first, step] => Ok(GoalCurve::Linear { first, step }),
+            _ => Err(invalid()),
+        },
+        "exp" => match numbers(':')?[..] {
+            [first, growth] => Ok(GoalCurve::Exponential { first, growth }),
+            _ => Err(invalid()),
+        },
+        "list" => Ok(GoalCurve::Custom(numbers(',')?)),
+        _ => Err(invalid()),
+    }
+}
+
+impl SurvivalOptions {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--machine" => options.machine = value()?,
+                "--pool" => options.pool = parse_number(&arg, &value()?)?,
+                "--bet" => options.bet = BetStrategy::Flat(parse_number(&arg, &value()?)?),
+                "--bet-fraction" => options.bet = BetStrategy::PoolFraction(parse_number(&arg, &value()?)?),
+                "--martingale" => {
+                    let value = value()?;
+                    let (base, max) = value.split_once(':').ok_or(format!("invalid martingale: {} (expected BASE:MAX)", value))?;
+                    options.bet = BetStrategy::Martingale {
+                        base: parse_number(&arg, base)?,
+                        max: parse_number(&arg, max)?,
+                    };
+                }
+                "--rolls" => options.rolls = parse_number(&arg, &value()?)?,
+                "--goals" => options.goals = parse_goals(&value()?)?,
+                "--levels" => options.levels = parse_number(&arg, &value()?)?,
+                "--runs" => options.runs = parse_number(&arg, &value()?)?,
+                "--seed" => options.seed = Some(parse_number(&arg, &value()?)?),
+                "--json" => options.json = Some(value()?),
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn main() {
+    let options = match SurvivalOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let machine = match load_machine(&options.machine) {
+        Ok(machine) => machine,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    validate_or_exit(&machine);
+
+    let config = SurvivalConfig {
+        starting_pool: options.pool,
+        bet: options.bet,
+        rolls_per_level: options.rolls,
+        goals: options.goals,
+        max_levels: options.levels,
+        runs: options.runs,
+        seed: options.seed.unwrap_or_else(|| thread_rng().next_u64()),
+    };
+    eprintln!("🎰 Playing {} runs of {} from a ${:.2} pool, {} rolls per goal...",
+        config.runs, options.machine, config.starting_pool, config.rolls_per_level);
+    let report = analyze_survival(&machine, &config);
+
+    let Some(path) = options.json else {
+        println!("{}", report);
+        return;
+    };
+    // Keep stdout clean for the JSON when it's going there
+    if path == "-" {
+        eprintln!("{}", report);
+    } else {
+        println!("{}", report);
+    }
+
+    let json = match serde_json::to_string_pretty(&report) {
+        Ok(json) => json,
+        Err(err) => {
+            eprintln!("⚠️ Failed to serialize the report: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let written = if path == "-" {
+        std::io::stdout().write_all(json.as_bytes())
+    } else {
+        File::create(&path).and_then(|mut file| file.write_all(json.as_bytes()))
+    };
+    if let Err(err) = written {
+        eprintln!("⚠️ Failed to write {}: {}", path, err);
+        std::process::exit(1);
+    }
+}
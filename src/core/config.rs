@@ -1,2 +1,64 @@
 // temp config setup (maybe I'll add a config.json along with this)
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+/// What to do after parsing the command line
+#[derive(Debug, Default, PartialEq)]
+pub enum LaunchCommand {
+    #[default]
+    Play,
+    Rtp, // print the machine's exact base game RTP table and exit
+    Validate, // list every problem in the machine definition and exit
+}
+
+/// Options passed on the command line, e.g. `cargo run -- --seed 42 --machine 5x3`
+/// or `cargo run -- rtp --machine 5x3` and `cargo run -- validate --machine my_machine.json`
+#[derive(Debug, Default)]
+pub struct LaunchOptions {
+    pub command: LaunchCommand,
+    pub seed: Option<u64>,
+    pub machine: Option<String>, // machine preset name or JSON file, the classic 3x3 when not given
+}
+
+// A flag's value as a number, shared by the game and the command line tools
+pub fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value for {}: {}", flag, value))
+}
+
+impl LaunchOptions {
+    pub fn from_args() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "rtp" => options.command = LaunchCommand::Rtp,
+                "validate" => options.command = LaunchCommand::Validate,
+                "--seed" => {
+                    let value = args.next().ok_or("--seed needs a number")?;
+                    options.seed = Some(parse_number(&arg, &value)?);
+                }
+                // Everyone playing on the same day gets the same seed
+                "--daily" => {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_err(|e| e.to_string())?;
+                    options.seed = Some(now.as_secs() / SECONDS_PER_DAY);
+                }
+                "--machine" => {
+                    let name = args.next().ok_or("--machine needs a preset name or file")?;
+                    options.machine = Some(name);
+                }
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
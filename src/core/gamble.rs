@@ -0,0 +1,92 @@
+use rand::Rng;
+
+/// Suits of the hidden gamble card
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suit {
+    Hearts,
+    Diamonds,
+    Clubs,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+    pub fn is_red(&self) -> bool {
+        matches!(self, Suit::Hearts | Suit::Diamonds)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Suit::Hearts => "HEARTS",
+            Suit::Diamonds => "DIAMONDS",
+            Suit::Clubs => "CLUBS",
+            Suit::Spades => "SPADES",
+        }
+    }
+}
+
+/// What the player thinks the hidden card is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GambleGuess {
+    Red,
+    Black,
+    Suit(Suit),
+}
+
+impl GambleGuess {
+    // The stake is multiplied by this on a correct guess
+    pub fn multiplier(&self) -> f32 {
+        match self {
+            GambleGuess::Red | GambleGuess::Black => 2.0,
+            GambleGuess::Suit(_) => 4.0,
+        }
+    }
+
+    pub fn matches(&self, card: Suit) -> bool {
+        match self {
+            GambleGuess::Red => card.is_red(),
+            GambleGuess::Black => !card.is_red(),
+            GambleGuess::Suit(suit) => *suit == card,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GambleGuess::Red => "RED",
+            GambleGuess::Black => "BLACK",
+            GambleGuess::Suit(suit) => suit.label(),
+        }
+    }
+}
+
+/// One gamble: the guess, the card that was drawn and what it paid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GambleOutcome {
+    pub guess: GambleGuess,
+    pub card: Suit,
+    pub stake: f32,
+    pub won: bool,
+}
+
+impl GambleOutcome {
+    pub fn payout(&self) -> f32 {
+        if self.won {
+            self.stake * self.guess.multiplier()
+        } else {
+            0.0
+        }
+    }
+}
+
+// Draw the hidden card and settle the guess. Both pays are fair (2x at 1/2, 4x at 1/4), so the
+// gamble doesn't change the machine's return
+pub fn gamble<R: Rng + ?Sized>(rng: &mut R, guess: GambleGuess, stake: f32) -> GambleOutcome {
+    let card = Suit::ALL[rng.gen_range(0..Suit::ALL.len())];
+    GambleOutcome {
+        guess,
+        card,
+        stake,
+        won: guess.matches(card),
+    }
+}
@@ -0,0 +1,442 @@
+// Exact slot math: walk every combination of reel stops, weighted by the strips, and add up what
+// each one pays. The paid spin itself is valued in full (line, ways, cluster and scatter wins
+// including cascades and mystery reveals), that's the base game RTP. Free spins and hold & spin
+// are valued on top where that stays exact: free spins are base game spins at the round's
+// multiplier, so they're worth the expected spins a round plays (retriggers and max_spins
+// included) times the base game RTP, unless sticky wilds tie the spins together. Hold & spin
+// rounds are walked through every board they can reach, counting coins per reel, on machines
+// whose reels don't change height. Jackpots, gambles, holds, nudges and sticky wilds carried
+// between spins aren't valued, slot-sim plays all of it for the machine's full RTP
+
+use std::collections::HashMap;
+use std::fmt;
+use crate::entities::slot_machine::{FreeSpinsConfig, HoldAndSpinConfig, SlotMachine, Symbol};
+use crate::core::scoring::{line_payout, outcome_payout};
+
+// Past this many spins the enumeration takes too long (Megaways machines are far past it),
+// simulate those machines instead
+pub const MAX_COMBINATIONS: u64 = 20_000_000;
+
+// Past this many boards (coins locked per reel) a hold & spin round isn't walked exactly
+const MAX_HOLD_AND_SPIN_BOARDS: usize = 10_000;
+
+/// What one paying symbol adds to the return
+#[derive(Debug, Clone)]
+pub struct SymbolContribution {
+    pub name: String,
+    pub rtp: f64, // share of the bet paid back by wins paying as this symbol
+    pub hit_frequency: f64, // chance a spin has at least one win paying as this symbol
+}
+
+/// Exact math for a machine, per unit bet
+#[derive(Debug, Clone)]
+pub struct RtpReport {
+    pub combinations: u64, // spins evaluated (stops x reel heights x mystery reveals)
+    pub rtp: f64, // the base game's share, what the paid spin itself pays
+    pub hit_frequency: f64, // chance a spin pays anything
+    pub symbols: Vec<SymbolContribution>, // highest contribution first
+    pub free_spins_frequency: f64,
+    pub hold_and_spin_frequency: f64,
+    pub free_spins_rtp: Option<f64>, // free spins' wins per paid spin, None when it can't be worked out exactly
+    pub hold_and_spin_rtp: Option<f64>, // coins collected per paid spin, during free spins too, None likewise
+}
+
+impl RtpReport {
+    // Base game and features together, when every part of it is exact
+    pub fn total_rtp(&self) -> Option<f64> {
+        Some(self.rtp + self.free_spins_rtp? + self.hold_and_spin_rtp?)
+    }
+}
+
+pub fn base_game_rtp(machine: &SlotMachine) -> Result<RtpReport, String> {
+    if machine.reels().is_empty() {
+        return Err("the machine has no reels".to_string());
+    }
+
+    // Every stop with its chance of being landed on, per reel
+    let mut stop_odds: Vec<Vec<(usize, f64)>> = Vec::new();
+    for (index, reel) in machine.reels().iter().enumerate() {
+        let total: f64 = reel.strip().iter().map(|stop| stop.weight as f64).sum();
+        if total <= 0.0 {
+            return Err(format!("reel {} has no weighted stops", index + 1));
+        }
+        stop_odds.push(reel.strip().iter()
+            .enumerate()
+            .filter(|(_, stop)| stop.weight > 0.0)
+            .map(|(position, stop)| (position, stop.weight as f64 / total))
+            .collect());
+    }
+
+    // Megaways reels land on every height with the same chance
+    let height_odds: Vec<Vec<usize>> = match machine.megaways() {
+        Some(config) => vec![(config.min_rows..=config.max_rows).collect(); machine.cols()],
+        None => vec![vec![machine.rows()]; machine.cols()],
+    };
+
+    let candidates = machine.mystery_candidates();
+    let candidate_total: f64 = candidates.iter().map(|&(_, weight)| weight as f64).sum();
+
+    // Stop before starting an enumeration that would never finish
+    if combinations(machine).is_none_or(|total| total > MAX_COMBINATIONS) {
+        return Err(format!(
+            "the machine has more than {} reel combinations, too many to enumerate exactly, measure it with slot-sim instead",
+            MAX_COMBINATIONS
+        ));
+    }
+
+    let mut report = RtpReport {
+        combinations: 0,
+        rtp: 0.0,
+        hit_frequency: 0.0,
+        symbols: Vec::new(),
+        free_spins_frequency: 0.0,
+        hold_and_spin_frequency: 0.0,
+        free_spins_rtp: None,
+        hold_and_spin_rtp: None,
+    };
+    // Chance of every board a hold & spin round starts from, keyed by the coins on each reel
+    let mut boards: HashMap<Vec<usize>, f64> = HashMap::new();
+    let height_chance = 1.0 / height_odds.iter().map(|heights| heights.len() as f64).product::<f64>();
+
+    for_each_combination(&stop_odds.iter().map(|odds| odds.len()).collect::<Vec<_>>(), |picks| {
+        let stops: Vec<usize> = picks.iter().enumerate().map(|(reel, &pick)| stop_odds[reel][pick].0).collect();
+        let stops_chance: f64 = picks.iter().enumerate().map(|(reel, &pick)| stop_odds[reel][pick].1).product();
+
+        for_each_combination(&height_odds.iter().map(|heights| heights.len()).collect::<Vec<_>>(), |picks| {
+            let heights: Vec<usize> = picks.iter().enumerate().map(|(reel, &pick)| height_odds[reel][pick]).collect();
+            let chance = stops_chance * height_chance;
+
+            // A grid with mystery symbols is worth the average over what they can reveal as. Cascades
+            // can drop mystery symbols in later, so on those machines every grid is averaged
+            let has_mystery = machine.cascade().is_some()
+                || machine.grid_at(&stops, &heights).iter().flatten().any(|symbol| symbol.is_mystery());
+            if has_mystery && !candidates.is_empty() {
+                for &(symbol, weight) in &candidates {
+                    let reveal_chance = chance * weight as f64 / candidate_total;
+                    add_spin(&mut report, &mut boards, machine, stops.clone(), heights.clone(), Some(symbol), reveal_chance);
+                }
+            } else {
+                add_spin(&mut report, &mut boards, machine, stops.clone(), heights, None, chance);
+            }
+        });
+    });
+
+    report.symbols.sort_by(|a, b| b.rtp.total_cmp(&a.rtp));
+
+    // Free spins play like paid spins, so every paid spin is followed by this many on average
+    let sticky = machine.reels().iter()
+        .flat_map(|reel| reel.strip())
+        .any(|stop| stop.symbol.wild_rules().is_some_and(|rules| rules.sticky_spins > 0));
+    let free_spins_per_spin = match machine.free_spins() {
+        None => Some(0.0),
+        Some(_) if sticky => None,
+        Some(config) => Some(report.free_spins_frequency * expected_free_spins(config, report.free_spins_frequency)),
+    };
+    report.free_spins_rtp = match machine.free_spins() {
+        None => Some(0.0),
+        Some(config) => free_spins_per_spin.map(|spins| spins * config.multiplier as f64 * report.rtp),
+    };
+    // Hold & spin pays at the bet, whether a paid spin or a free one started it
+    report.hold_and_spin_rtp = match machine.hold_and_spin() {
+        None => Some(0.0),
+        Some(config) => hold_and_spin_value(machine, config, &boards)
+            .zip(free_spins_per_spin)
+            .map(|(value, free_spins)| value * (1.0 + free_spins)),
+    };
+    Ok(report)
+}
+
+// Free spins a round plays on average: `spins` to start with, then every free spin retriggers
+// with the trigger's chance (when the machine allows it) until the round has awarded max_spins.
+// Walks the chance of each total awarded so far, spin by spin, until every round is over
+pub fn expected_free_spins(config: &FreeSpinsConfig, trigger: f64) -> f64 {
+    let retrigger = if config.retrigger { trigger } else { 0.0 };
+    let first = config.capped(0, config.spins) as usize;
+    if first == 0 {
+        return 0.0;
+    }
+
+    // Chance a round is still going having awarded [index] spins
+    let mut awarded = vec![0.0; config.max_spins.max(config.spins) as usize + 1];
+    awarded[first] = 1.0;
+    let mut expected = 0.0;
+    for played in 1..awarded.len() {
+        let mut next = vec![0.0; awarded.len()];
+        for (total, &chance) in awarded.iter().enumerate().filter(|&(_, &chance)| chance > 0.0) {
+            let more = total + config.capped(total as u32, config.spins) as usize;
+            next[more] += chance * retrigger;
+            next[total] += chance * (1.0 - retrigger);
+        }
+        // Rounds that have now played every spin they awarded are over
+        expected += next[played] * played as f64;
+        next[played] = 0.0;
+        awarded = next;
+    }
+    expected
+}
+
+// Coins a hold & spin round collects per paid spin, in bets: every starting board walked through
+// every respin it can see, with the coin chance of each reel and the average coin value. Cells on
+// the same reel land coins alike, so a board is just the coins locked on each reel. None on
+// machines whose reels change height or with too many boards to walk
+fn hold_and_spin_value(machine: &SlotMachine, config: &HoldAndSpinConfig, boards: &HashMap<Vec<usize>, f64>) -> Option<f64> {
+    if machine.megaways().is_some() {
+        return None;
+    }
+    let rows = machine.rows();
+    if (rows + 1).checked_pow(machine.cols() as u32).is_none_or(|count| count > MAX_HOLD_AND_SPIN_BOARDS) {
+        return None;
+    }
+
+    let coin_chance: Vec<f64> = machine.reels().iter()
+        .map(|reel| {
+            let total: f64 = reel.strip().iter().map(|stop| stop.weight as f64).sum();
+            let coins: f64 = reel.strip().iter().filter(|stop| stop.symbol.is_coin()).map(|stop| stop.weight as f64).sum();
+            coins / total
+        })
+        .collect();
+    let weights: f64 = config.coin_values.iter().map(|&(_, weight)| weight as f64).sum();
+    let coin_value = if weights > 0.0 {
+        config.coin_values.iter().map(|&(value, weight)| value as f64 * weight as f64).sum::<f64>() / weights
+    } else {
+        1.0
+    };
+
+    let mut memo = HashMap::new();
+    let coins: f64 = boards.iter()
+        .map(|(locked, chance)| chance * final_coins(locked, config.respins, config.respins, rows, &coin_chance, &mut memo))
+        .sum();
+    Some(coins * coin_value)
+}
+
+// Coins a board ends with on average, from `locked` coins per reel and `respins` left
+fn final_coins(
+    locked: &[usize],
+    respins: u32,
+    reset: u32,
+    rows: usize,
+    coin_chance: &[f64],
+    memo: &mut HashMap<(Vec<usize>, u32), f64>,
+) -> f64 {
+    let coins: usize = locked.iter().sum();
+    if respins == 0 || coins == rows * locked.len() {
+        return coins as f64;
+    }
+    if let Some(&known) = memo.get(&(locked.to_vec(), respins)) {
+        return known;
+    }
+
+    // Every way the open cells can land: [reel] new coins, with the chance of that many on each reel
+    let open: Vec<usize> = locked.iter().map(|&count| rows - count).collect();
+    let mut expected = 0.0;
+    for_each_combination(&open.iter().map(|&cells| cells + 1).collect::<Vec<_>>(), |landed| {
+        let chance: f64 = landed.iter().enumerate()
+            .map(|(reel, &new)| binomial(open[reel], new, coin_chance[reel]))
+            .product();
+        if chance == 0.0 {
+            return;
+        }
+        let next = if landed.iter().all(|&new| new == 0) {
+            final_coins(locked, respins - 1, reset, rows, coin_chance, memo)
+        } else {
+            let more: Vec<usize> = locked.iter().zip(landed).map(|(&count, &new)| count + new).collect();
+            final_coins(&more, reset, reset, rows, coin_chance, memo)
+        };
+        expected += chance * next;
+    });
+
+    memo.insert((locked.to_vec(), respins), expected);
+    expected
+}
+
+// Chance of exactly `hits` out of `trials`, each landing with `chance`
+fn binomial(trials: usize, hits: usize, chance: f64) -> f64 {
+    let ways: f64 = (0..hits).map(|i| (trials - i) as f64 / (i + 1) as f64).product();
+    ways * chance.powi(hits as i32) * (1.0 - chance).powi((trials - hits) as i32)
+}
+
+// Most spins base_game_rtp evaluates for a machine (stops x reel heights x mystery reveals), None
+// when that doesn't even fit a u64
+pub fn combinations(machine: &SlotMachine) -> Option<u64> {
+    let heights = match machine.megaways() {
+        Some(config) => (config.max_rows + 1).saturating_sub(config.min_rows) as u64,
+        None => 1,
+    };
+    machine.reels().iter()
+        .map(|reel| reel.strip().iter().filter(|stop| stop.weight > 0.0).count() as u64)
+        .chain(std::iter::repeat_n(heights, machine.cols()))
+        .chain(std::iter::once(machine.mystery_candidates().len().max(1) as u64))
+        .try_fold(1u64, |total, count| total.checked_mul(count))
+}
+
+// Evaluate one spin and add it to the report, weighted by its chance
+fn add_spin(
+    report: &mut RtpReport,
+    boards: &mut HashMap<Vec<usize>, f64>,
+    machine: &SlotMachine,
+    stops: Vec<usize>,
+    heights: Vec<usize>,
+    mystery: Option<&Symbol>,
+    chance: f64,
+) {
+    let outcome = machine.outcome_with_modifiers(stops, heights, &[], mystery);
+    report.combinations += 1;
+
+    let payout = outcome_payout(&outcome, 1.0) as f64;
+    report.rtp += payout * chance;
+    if payout > 0.0 {
+        report.hit_frequency += chance;
+    }
+
+    // Each symbol's share, a symbol paying on several lines only counts once for its hit rate
+    let mut paid_symbols: Vec<&str> = Vec::new();
+    for step in &outcome.steps {
+        for win in &step.wins {
+            let name = win.symbol.name.as_str();
+            let paid = (line_payout(win, 1.0) * step.multiplier) as f64;
+            let index = match report.symbols.iter().position(|entry| entry.name == name) {
+                Some(index) => index,
+                None => {
+                    report.symbols.push(SymbolContribution {
+                        name: name.to_string(),
+                        rtp: 0.0,
+                        hit_frequency: 0.0,
+                    });
+                    report.symbols.len() - 1
+                }
+            };
+            report.symbols[index].rtp += paid * chance;
+            if !paid_symbols.contains(&name) {
+                paid_symbols.push(name);
+                report.symbols[index].hit_frequency += chance;
+            }
+        }
+    }
+
+    let grid = outcome.initial_grid();
+    if machine.free_spins_awarded(grid) > 0 {
+        report.free_spins_frequency += chance;
+    }
+    if let Some(config) = machine.hold_and_spin() {
+        let coins = grid.iter().flatten().filter(|symbol| symbol.is_coin()).count();
+        if coins >= config.trigger_count {
+            report.hold_and_spin_frequency += chance;
+            let per_reel = (0..machine.cols())
+                .map(|col| grid.iter().filter(|row| row.get(col).is_some_and(Symbol::is_coin)).count())
+                .collect();
+            *boards.entry(per_reel).or_default() += chance;
+        }
+    }
+}
+
+// Call `f` with every combination of indexes, one per slot, the last slot turning fastest
+fn for_each_combination(sizes: &[usize], mut f: impl FnMut(&[usize])) {
+    if sizes.contains(&0) {
+        return;
+    }
+    let mut picks = vec![0; sizes.len()];
+    loop {
+        f(&picks);
+
+        let mut slot = sizes.len();
+        loop {
+            if slot == 0 {
+                return;
+            }
+            slot -= 1;
+            picks[slot] += 1;
+            if picks[slot] < sizes[slot] {
+                break;
+            }
+            picks[slot] = 0;
+        }
+    }
+}
+
+// "1 in N" for a frequency, or "never"
+pub fn one_in(frequency: f64) -> String {
+    if frequency > 0.0 {
+        format!("1 in {:.1}", 1.0 / frequency)
+    } else {
+        "never".to_string()
+    }
+}
+
+impl fmt::Display for RtpReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<12} {:>10} {:>12}", "Symbol", "RTP", "Hit freq")?;
+        for symbol in &self.symbols {
+            writeln!(f, "{:<12} {:>9.3}% {:>11.4}%", symbol.name, symbol.rtp * 100.0, symbol.hit_frequency * 100.0)?;
+        }
+        writeln!(f, "{}", "-".repeat(36))?;
+        writeln!(f, "{:<20} {:>9.3}%", "Base game RTP", self.rtp * 100.0)?;
+        let feature = |value: Option<f64>| match value {
+            Some(value) => format!("{:>9.3}%", value * 100.0),
+            None => format!("{:>10}", "not exact"),
+        };
+        writeln!(f, "{:<20} {}", "Free spins RTP", feature(self.free_spins_rtp))?;
+        writeln!(f, "{:<20} {}", "Hold & spin RTP", feature(self.hold_and_spin_rtp))?;
+        writeln!(f, "{:<20} {}", "Total RTP", feature(self.total_rtp()))?;
+        writeln!(f, "Hit frequency: {:.3}% ({})", self.hit_frequency * 100.0, one_in(self.hit_frequency))?;
+        writeln!(f, "Free spins trigger: {}", one_in(self.free_spins_frequency))?;
+        writeln!(f, "Hold & spin trigger: {}", one_in(self.hold_and_spin_frequency))?;
+        writeln!(f, "Exact over {} weighted combinations", self.combinations)?;
+        write!(f, "Jackpots and sticky wilds carried between spins aren't included, slot-sim measures the full RTP")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::fixtures::coin_flip_machine;
+    use crate::entities::slot_machine::{MegawaysConfig, Payline, Reel, ReelStop, SymbolKind};
+
+    #[test]
+    fn enumerates_a_hand_computable_machine() {
+        let report = base_game_rtp(&coin_flip_machine()).unwrap();
+
+        assert_eq!(report.combinations, 8);
+        assert!((report.rtp - 0.75).abs() < 1e-9);
+        assert!((report.hit_frequency - 0.25).abs() < 1e-9);
+        assert_eq!(report.symbols[0].name, "Lemon");
+        assert!((report.symbols[0].rtp - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn free_spins_are_worth_the_spins_a_round_plays() {
+        // Needing no scatters, every spin triggers: 10 spins at 2x, each worth the 75% base game
+        let config = FreeSpinsConfig { trigger_count: 0, spins: 10, multiplier: 2.0, retrigger: false, max_spins: 100 };
+        let report = base_game_rtp(&coin_flip_machine().with_free_spins(config.clone())).unwrap();
+        assert!((report.free_spins_rtp.unwrap() - 15.0).abs() < 1e-9);
+        assert!((report.total_rtp().unwrap() - 15.75).abs() < 1e-9);
+
+        // Each spin starts 1 in 20 extra rounds of 10, so a round plays 10 / (1 - 0.5) spins
+        let retriggering = FreeSpinsConfig { retrigger: true, max_spins: 10_000, ..config };
+        assert!((expected_free_spins(&retriggering, 0.05) - 20.0).abs() < 1e-6);
+        // Always retriggering, the round plays until it reaches max_spins
+        assert!((expected_free_spins(&FreeSpinsConfig { max_spins: 45, ..retriggering }, 1.0) - 45.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hold_and_spin_walks_every_respin() {
+        // Two one-row reels, half coin and half blank, one coin starts the round with one respin.
+        // Both coins (1 in 4) collect 2, a single coin (1 in 2) gets one more try at the other: 1.5
+        let reel = Reel::from_strip(vec![
+            ReelStop::new(Symbol::new("🪙", "Coin", 0.0, 0.0, 1.0).with_pays(&[]).with_kind(SymbolKind::Coin), 1.0),
+            ReelStop::new(Symbol::new("🍒", "Cherry", 1.0, 0.0, 1.0), 1.0),
+        ]);
+        let machine = SlotMachine::new(vec![reel.clone(), reel])
+            .with_rows(1)
+            .with_paylines(vec![Payline::new(&[0, 0])])
+            .with_hold_and_spin(HoldAndSpinConfig { trigger_count: 1, respins: 1, coin_values: vec![(1.0, 1.0)] });
+        let report = base_game_rtp(&machine).unwrap();
+        assert!((report.hold_and_spin_rtp.unwrap() - 1.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn refuses_machines_too_large_to_enumerate() {
+        let machine = coin_flip_machine().with_megaways(MegawaysConfig { min_rows: 1, max_rows: 200 });
+        assert!(base_game_rtp(&machine).is_err());
+        assert!(base_game_rtp(&SlotMachine::megaways_6()).is_err());
+    }
+}
@@ -1 +1,9 @@
-pub mod scoring;
\ No newline at end of file
+pub mod config;
+pub mod gamble;
+pub mod math;
+pub mod rng;
+pub mod scoring;
+pub mod simulation;
+pub mod solver;
+pub mod survival;
+pub mod validation;
\ No newline at end of file
@@ -0,0 +1,31 @@
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+/// Game-wide RNG resource. Every outcome draws from here so a session can be replayed from its seed
+#[cfg_attr(feature = "ui", derive(bevy::prelude::Resource))]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    // Pick a random seed but still remember it, so unseeded sessions can be replayed too
+    pub fn from_entropy() -> Self {
+        Self::from_seed(thread_rng().next_u64())
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+}
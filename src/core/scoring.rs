@@ -1,14 +1,22 @@
-use crate::entities::slot_machine::{WinningLine, Symbol};
+use crate::entities::slot_machine::{WinningLine, Symbol, SpinOutcome};
 
 pub fn line_payout(line: &WinningLine, bet: f32) -> f32 {
-    let s: &Symbol = line.symbols[0];
-    s.multiplier * bet + s.addition
+    let s: &Symbol = &line.symbol;
+    let multiplier = s.pay_for(line.count).unwrap_or(0.0) * line.multiplier * line.ways as f32;
+    multiplier * bet + s.addition
 }
 
 pub fn total_payout(wins: &[WinningLine], bet: f32) -> f32 {
     wins.iter().map(|line| line_payout(line, bet)).sum()
 }
 
+// Every cascade step pays with its own multiplier
+pub fn outcome_payout(outcome: &SpinOutcome, bet: f32) -> f32 {
+    outcome.steps.iter()
+        .map(|step| total_payout(&step.wins, bet) * step.multiplier)
+        .sum()
+}
+
 pub fn update_pool(current_pool: &mut f32, wins: &[WinningLine], bet: f32) {
     let payout = total_payout(wins, bet);
     *current_pool += payout;
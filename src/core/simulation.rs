@@ -0,0 +1,383 @@
+// Monte Carlo slot math: play a machine for millions of paid spins and measure what it pays.
+// Every spin is played out the way the game settles it: sticky wilds carried from spin to spin,
+// mystery reveals rolled, and any free spins and hold & spin rounds it starts played to the end,
+// so the RTP is the machine's full return, split into base game and feature shares. Besides the
+// averages it records the shape of the wins: a histogram, how often big wins land and the
+// longest run of losing spins. Jackpots, gambles, holds and nudges aren't played
+
+use std::fmt;
+use serde::Serialize;
+use rand::Rng;
+use crate::entities::slot_machine::{SlotMachine, SpinOutcome, StickyWild};
+use crate::core::rng::GameRng;
+use crate::core::math::one_in;
+use crate::core::scoring::outcome_payout;
+
+// Two-sided 95% normal quantile
+const Z_95: f64 = 1.96;
+
+// Upper edges of the win histogram buckets in bets, the last bucket has no upper edge
+const BUCKET_EDGES: [f64; 9] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 500.0, 1000.0];
+
+// Win sizes, in bets, to report the chance of reaching
+const MILESTONES: [f64; 3] = [10.0, 100.0, 1000.0];
+
+/// How long and at what stake to simulate
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    pub spins: u64,
+    pub bet: f32,
+    pub seed: u64,
+}
+
+/// Results of a simulation run, returns and wins are measured in bets
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationReport {
+    pub spins: u64,
+    pub bet: f32,
+    pub seed: u64,
+    pub total_bet: f64,
+    pub total_won: f64,
+    pub rtp: f64,
+    pub rtp_ci_95: (f64, f64),
+    pub base_rtp: f64, // the paid spins' own wins
+    pub free_spins_rtp: f64, // free spins, at the round's multiplier
+    pub hold_and_spin_rtp: f64, // coins collected in hold & spin rounds
+    pub hit_frequency: f64,
+    pub hit_frequency_ci_95: (f64, f64),
+    pub volatility: f64, // standard deviation of the return of a single spin
+    pub max_win: f64,
+    pub free_spins_frequency: f64, // paid spins starting free spins
+    pub free_spins_capped: u64, // free spins rounds cut short by the machine's max_spins
+    pub hold_and_spin_frequency: f64, // paid spins leading to hold & spin, during its free spins too
+    pub distribution: WinDistribution,
+}
+
+/// Everything one paid spin won, split by where it came from
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RoundWin {
+    pub base: f32,
+    pub free_spins: f32,
+    pub hold_and_spin: f32,
+    pub free_spins_triggered: bool,
+    pub hold_and_spin_triggered: bool,
+    pub free_spins_awarded: u32, // including retriggers, never more than the machine's max_spins
+    pub free_spins_capped: bool, // a trigger awarded fewer spins than usual because of max_spins
+}
+
+impl RoundWin {
+    pub fn total(&self) -> f32 {
+        self.base + self.free_spins + self.hold_and_spin
+    }
+}
+
+/// Spins whose win, in bets, fell in `min..max` (only losing spins in the first bucket)
+#[derive(Debug, Clone, Serialize)]
+pub struct WinBucket {
+    pub label: String,
+    pub min: f64,
+    pub max: Option<f64>, // None for the last, open ended bucket
+    pub spins: u64,
+    pub probability: f64,
+}
+
+/// Chance of a single spin winning at least `multiplier` bets
+#[derive(Debug, Clone, Serialize)]
+pub struct WinMilestone {
+    pub multiplier: f64,
+    pub spins: u64,
+    pub probability: f64,
+}
+
+/// Shape of the win distribution of a simulation run
+#[derive(Debug, Clone, Serialize)]
+pub struct WinDistribution {
+    pub buckets: Vec<WinBucket>, // losses first, then wins from small to big
+    pub milestones: Vec<WinMilestone>,
+    pub longest_losing_streak: u64,
+}
+
+// One spin the way the game plays it: fresh stops and reel heights, a mystery reveal rolled and
+// the sticky wilds from the previous spin placed, which are then swapped for the next spin's
+pub fn play_spin<R: Rng + ?Sized>(machine: &SlotMachine, rng: &mut R, sticky: &mut Vec<StickyWild>) -> SpinOutcome {
+    let stops = machine.spin_stops_with_rng(rng);
+    let heights = machine.spin_heights_with_rng(rng);
+    let mystery = machine.roll_mystery(rng);
+    let outcome = machine.outcome_with_modifiers(stops, heights, sticky, mystery.as_ref());
+    *sticky = outcome.sticky_wilds.clone();
+    outcome
+}
+
+// Everything one paid spin wins: the spin itself, then any free spins and hold & spin rounds it
+// starts, played out like settle_spin_outcome does. Free spins pay at the paid spin's bet and the
+// round's multiplier, and only retrigger when the machine allows it, up to the round's max_spins
+pub fn play_round<R: Rng + ?Sized>(machine: &SlotMachine, rng: &mut R, sticky: &mut Vec<StickyWild>, bet: f32) -> RoundWin {
+    let mut round = RoundWin::default();
+    let mut free_spins = 0;
+    let mut multiplier = 1.0;
+    let mut spin_is_free = false;
+
+    loop {
+        let outcome = play_spin(machine, rng, sticky);
+        let grid = outcome.initial_grid();
+        let won = outcome_payout(&outcome, bet) * multiplier;
+        if spin_is_free {
+            round.free_spins += won;
+        } else {
+            round.base += won;
+        }
+
+        if let Some(config) = machine.free_spins() {
+            let awarded = machine.free_spins_awarded(grid);
+            if awarded > 0 && (!spin_is_free || config.retrigger) {
+                let capped = config.capped(round.free_spins_awarded, awarded);
+                round.free_spins_capped |= capped < awarded;
+                round.free_spins_awarded += capped;
+                free_spins += capped;
+                multiplier = config.multiplier;
+                round.free_spins_triggered = true;
+            }
+        }
+
+        if let Some(mut board) = machine.hold_and_spin_trigger(rng, grid) {
+            while !board.is_over() {
+                machine.respin_with_rng(rng, &mut board);
+            }
+            round.hold_and_spin += board.total_value() * bet;
+            round.hold_and_spin_triggered = true;
+        }
+
+        if free_spins == 0 {
+            return round;
+        }
+        free_spins -= 1;
+        spin_is_free = true;
+    }
+}
+
+pub fn simulate(machine: &SlotMachine, config: &SimulationConfig) -> SimulationReport {
+    let mut game_rng = GameRng::from_seed(config.seed);
+    let mut sticky: Vec<StickyWild> = Vec::new();
+
+    // Running mean and variance of the per spin return (Welford)
+    let mut mean = 0.0;
+    let mut squares = 0.0;
+    let mut total_won = 0.0;
+    let (mut base_won, mut free_spins_won, mut hold_and_spin_won) = (0.0, 0.0, 0.0);
+    let mut hits = 0u64;
+    let mut max_win: f64 = 0.0;
+    let mut free_spins = 0u64;
+    let mut free_spins_capped = 0u64;
+    let mut hold_and_spin = 0u64;
+    let mut distribution = WinDistribution::new();
+    let mut losing_streak = 0u64;
+
+    for spin in 1..=config.spins {
+        let round = play_round(machine, game_rng.rng(), &mut sticky, config.bet);
+
+        let won = round.total() as f64;
+        total_won += won;
+        base_won += round.base as f64;
+        free_spins_won += round.free_spins as f64;
+        hold_and_spin_won += round.hold_and_spin as f64;
+        if won > 0.0 {
+            hits += 1;
+            losing_streak = 0;
+        } else {
+            losing_streak += 1;
+            distribution.longest_losing_streak = distribution.longest_losing_streak.max(losing_streak);
+        }
+
+        let ratio = won / config.bet as f64;
+        distribution.record(ratio);
+        max_win = max_win.max(ratio);
+        let delta = ratio - mean;
+        mean += delta / spin as f64;
+        squares += delta * (ratio - mean);
+
+        free_spins += round.free_spins_triggered as u64;
+        free_spins_capped += round.free_spins_capped as u64;
+        hold_and_spin += round.hold_and_spin_triggered as u64;
+    }
+
+    let spins = config.spins.max(1) as f64;
+    let volatility = if config.spins > 1 { (squares / (spins - 1.0)).sqrt() } else { 0.0 };
+    let rtp_margin = Z_95 * volatility / spins.sqrt();
+    let hit_frequency = hits as f64 / spins;
+    let hit_margin = Z_95 * (hit_frequency * (1.0 - hit_frequency) / spins).sqrt();
+    distribution.finish(spins);
+    let total_bet = config.bet as f64 * config.spins as f64;
+    let share = |won: f64| if total_bet > 0.0 { won / total_bet } else { 0.0 };
+
+    SimulationReport {
+        spins: config.spins,
+        bet: config.bet,
+        seed: config.seed,
+        total_bet,
+        total_won,
+        rtp: mean,
+        rtp_ci_95: (mean - rtp_margin, mean + rtp_margin),
+        base_rtp: share(base_won),
+        free_spins_rtp: share(free_spins_won),
+        hold_and_spin_rtp: share(hold_and_spin_won),
+        hit_frequency,
+        hit_frequency_ci_95: ((hit_frequency - hit_margin).max(0.0), (hit_frequency + hit_margin).min(1.0)),
+        volatility,
+        max_win,
+        free_spins_frequency: free_spins as f64 / spins,
+        free_spins_capped,
+        hold_and_spin_frequency: hold_and_spin as f64 / spins,
+        distribution,
+    }
+}
+
+impl WinDistribution {
+    fn new() -> Self {
+        let mut buckets = vec![WinBucket { label: "0x".to_string(), min: 0.0, max: Some(0.0), spins: 0, probability: 0.0 }];
+        let mut min = 0.0;
+        for edge in BUCKET_EDGES {
+            buckets.push(WinBucket { label: format!("{}-{}x", min, edge), min, max: Some(edge), spins: 0, probability: 0.0 });
+            min = edge;
+        }
+        buckets.push(WinBucket { label: format!("{}x+", min), min, max: None, spins: 0, probability: 0.0 });
+
+        Self {
+            buckets,
+            milestones: MILESTONES.iter()
+                .map(|&multiplier| WinMilestone { multiplier, spins: 0, probability: 0.0 })
+                .collect(),
+            longest_losing_streak: 0,
+        }
+    }
+
+    // Count one spin that won `ratio` bets
+    fn record(&mut self, ratio: f64) {
+        let bucket = if ratio > 0.0 {
+            1 + BUCKET_EDGES.iter().take_while(|&&edge| ratio >= edge).count()
+        } else {
+            0
+        };
+        self.buckets[bucket].spins += 1;
+
+        for milestone in &mut self.milestones {
+            if ratio >= milestone.multiplier {
+                milestone.spins += 1;
+            }
+        }
+    }
+
+    fn finish(&mut self, spins: f64) {
+        for bucket in &mut self.buckets {
+            bucket.probability = bucket.spins as f64 / spins;
+        }
+        for milestone in &mut self.milestones {
+            milestone.probability = milestone.spins as f64 / spins;
+        }
+    }
+
+    // The histogram as CSV, with the chance of winning at least each bucket's lower edge
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("bucket,min_x,max_x,spins,probability,at_least_min_probability\n");
+        let spins: u64 = self.buckets.iter().map(|bucket| bucket.spins).sum();
+        let mut at_least = spins;
+        for bucket in &self.buckets {
+            let max = bucket.max.map(|max| max.to_string()).unwrap_or_default();
+            csv.push_str(&format!("{},{},{},{},{},{}\n",
+                bucket.label, bucket.min, max, bucket.spins, bucket.probability, at_least as f64 / spins.max(1) as f64));
+            at_least -= bucket.spins;
+        }
+        csv
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Spins:           {} at ${:.2} (seed {})", self.spins, self.bet, self.seed)?;
+        writeln!(f, "Total bet / won: ${:.2} / ${:.2}", self.total_bet, self.total_won)?;
+        writeln!(f, "RTP:             {:.3}% (95% CI {:.3}% - {:.3}%)",
+            self.rtp * 100.0, self.rtp_ci_95.0 * 100.0, self.rtp_ci_95.1 * 100.0)?;
+        writeln!(f, "  Base game:     {:.3}%", self.base_rtp * 100.0)?;
+        writeln!(f, "  Free spins:    {:.3}%", self.free_spins_rtp * 100.0)?;
+        writeln!(f, "  Hold & spin:   {:.3}%", self.hold_and_spin_rtp * 100.0)?;
+        writeln!(f, "Hit frequency:   {:.3}% (95% CI {:.3}% - {:.3}%)",
+            self.hit_frequency * 100.0, self.hit_frequency_ci_95.0 * 100.0, self.hit_frequency_ci_95.1 * 100.0)?;
+        writeln!(f, "Volatility:      {:.3} (std dev per spin, in bets)", self.volatility)?;
+        writeln!(f, "Max win:         {:.2}x", self.max_win)?;
+        writeln!(f, "Free spins:      {:.4}% of spins", self.free_spins_frequency * 100.0)?;
+        if self.free_spins_capped > 0 {
+            writeln!(f, "  Capped:        {} rounds hit the free spins limit", self.free_spins_capped)?;
+        }
+        writeln!(f, "Hold & spin:     {:.4}% of spins", self.hold_and_spin_frequency * 100.0)?;
+        write!(f, "{}", self.distribution)
+    }
+}
+
+impl fmt::Display for WinDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Longest losing streak: {} spins", self.longest_losing_streak)?;
+        for milestone in &self.milestones {
+            writeln!(f, "Win {}x or more: {:.4}% of spins ({})",
+                milestone.multiplier, milestone.probability * 100.0, one_in(milestone.probability))?;
+        }
+        writeln!(f, "{:<12} {:>12} {:>10}", "Win", "Spins", "Share")?;
+        for (index, bucket) in self.buckets.iter().enumerate() {
+            let bar = "█".repeat((bucket.probability * 40.0).ceil() as usize);
+            write!(f, "{:<12} {:>12} {:>9.4}% {}", bucket.label, bucket.spins, bucket.probability * 100.0, bar)?;
+            if index + 1 < self.buckets.len() {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::fixtures::coin_flip_machine;
+    use crate::entities::slot_machine::FreeSpinsConfig;
+
+    #[test]
+    fn retriggers_stop_at_the_free_spins_limit() {
+        // Needing no scatters at all, every spin (free ones too) retriggers
+        let machine = coin_flip_machine().with_free_spins(FreeSpinsConfig {
+            trigger_count: 0,
+            spins: 7,
+            max_spins: 50,
+            ..FreeSpinsConfig::default()
+        });
+        let mut rng = GameRng::from_seed(3);
+        let round = play_round(&machine, rng.rng(), &mut Vec::new(), 1.0);
+
+        assert_eq!(round.free_spins_awarded, 50);
+        assert!(round.free_spins_capped);
+
+        let report = simulate(&machine, &SimulationConfig { spins: 20, bet: 1.0, seed: 3 });
+        assert_eq!(report.free_spins_capped, 20);
+    }
+
+    #[test]
+    fn free_spins_rounds_on_every_preset_end() {
+        for name in SlotMachine::PRESETS {
+            let machine = SlotMachine::preset(name).unwrap();
+            let Some(config) = machine.free_spins() else { continue };
+            let mut rng = GameRng::from_seed(7);
+
+            // Play until five rounds have had free spins, none of them should run into the limit
+            let mut rounds = 0;
+            for _ in 0..50_000 {
+                let round = play_round(&machine, rng.rng(), &mut Vec::new(), 1.0);
+                if !round.free_spins_triggered {
+                    continue;
+                }
+                assert!(!round.free_spins_capped, "{name}: a free spins round hit the limit");
+                assert!(round.free_spins_awarded < config.max_spins, "{name}: {} free spins", round.free_spins_awarded);
+                rounds += 1;
+                if rounds == 5 {
+                    break;
+                }
+            }
+            assert_eq!(rounds, 5, "{name}: free spins hardly ever start");
+        }
+    }
+}
@@ -0,0 +1,236 @@
+// Weight solver: tune how common each symbol is until a machine pays a target RTP, and
+// optionally hits a target hit frequency. Only weights move, pays, paylines and features stay as
+// designed. By default each symbol keeps one chance across the reels (see
+// SlotMachine::with_symbol_chance). Since a reel shows consecutive stops, identical strips keep
+// lining the same symbols up however they're weighted, so the solver can also weight every
+// reel's stops on their own, letting the reels land out of step. Every machine is measured the
+// same way, with a fixed-seed simulation playing whole rounds like slot-sim (features and sticky
+// wilds included), so candidates are compared on the same spins and on the full RTP
+
+use crate::entities::slot_machine::SlotMachine;
+use crate::core::simulation::{simulate, SimulationConfig};
+
+// Spins per measurement unless the targets ask for more or fewer
+pub const DEFAULT_SPINS: u64 = 200_000;
+const SIMULATION_SEED: u64 = 1;
+
+// Lowest weight the solver gives a symbol, a weight of 0 would make a symbol that never lands
+// and could never be scaled back up
+const MIN_WEIGHT: f32 = 0.001;
+
+// First step multiplies or divides a chance by this, the step shrinks when nothing improves
+const START_FACTOR: f32 = 2.0;
+const MIN_FACTOR: f32 = 1.001;
+
+/// Lowest and highest chance a symbol may be given
+#[derive(Debug, Clone, Copy)]
+pub struct WeightBounds {
+    pub min: f32,
+    pub max: f32,
+}
+
+/// What the solver aims for, RTP and hit frequency as fractions (0.95 for 95%)
+#[derive(Debug, Clone)]
+pub struct SolverTargets {
+    pub rtp: f64,
+    pub hit_frequency: Option<f64>,
+    pub tolerance: f64, // how close each target has to get
+    pub bounds: WeightBounds, // for every symbol without its own bounds
+    pub symbol_bounds: Vec<(String, WeightBounds)>,
+    pub per_reel: bool, // weight each reel's stops separately instead of one chance per symbol
+    pub max_evaluations: usize,
+    pub spins: u64, // simulated spins per measurement
+}
+
+/// A symbol's weight before and after solving
+#[derive(Debug, Clone)]
+pub struct SolvedWeight {
+    pub name: String,
+    pub reel: Option<usize>, // the reel it was weighted on, None for the symbol's chance on every reel
+    pub before: f32,
+    pub after: f32,
+}
+
+/// The tuned machine and how close it got
+#[derive(Debug, Clone)]
+pub struct SolverResult {
+    pub machine: SlotMachine,
+    pub weights: Vec<SolvedWeight>,
+    pub rtp: f64,
+    pub hit_frequency: f64,
+    pub converged: bool, // every target within tolerance
+    pub evaluations: usize,
+}
+
+impl SolverTargets {
+    // The bounds a symbol's weight moves in, never down to 0
+    fn bounds_for(&self, name: &str) -> WeightBounds {
+        let bounds = self.symbol_bounds.iter()
+            .find(|(symbol, _)| symbol == name)
+            .map(|&(_, bounds)| bounds)
+            .unwrap_or(self.bounds);
+        WeightBounds { min: bounds.min.max(MIN_WEIGHT), max: bounds.max.max(MIN_WEIGHT) }
+    }
+
+    // How far a measurement is from the targets, in tolerances squared
+    fn error(&self, rtp: f64, hit_frequency: f64) -> f64 {
+        let tolerance = self.tolerance.max(f64::EPSILON);
+        let rtp_error = (rtp - self.rtp) / tolerance;
+        let hit_error = self.hit_frequency.map_or(0.0, |target| (hit_frequency - target) / tolerance);
+        rtp_error * rtp_error + hit_error * hit_error
+    }
+
+    fn is_met(&self, rtp: f64, hit_frequency: f64) -> bool {
+        (rtp - self.rtp).abs() <= self.tolerance
+            && self.hit_frequency.is_none_or(|target| (hit_frequency - target).abs() <= self.tolerance)
+    }
+}
+
+// Pattern search over the weights: scale each one up and down by a factor, keep the best
+// move while moves still help and shrink the factor once none do
+pub fn solve(machine: &SlotMachine, targets: &SolverTargets) -> Result<SolverResult, String> {
+    if machine.reels().is_empty() {
+        return Err("the machine has no reels".to_string());
+    }
+
+    // Every weight the solver can move, with the value it starts from
+    let mut names: Vec<String> = Vec::new();
+    let mut variables: Vec<(Option<usize>, String)> = Vec::new();
+    let mut before: Vec<f32> = Vec::new();
+    for (index, reel) in machine.reels().iter().enumerate() {
+        for stop in reel.strip() {
+            if !names.contains(&stop.symbol.name) {
+                names.push(stop.symbol.name.clone());
+            }
+            let variable = (targets.per_reel.then_some(index), stop.symbol.name.clone());
+            if !variables.contains(&variable) {
+                variables.push(variable);
+                before.push(if targets.per_reel { stop.weight } else { stop.symbol.chance });
+            }
+        }
+    }
+    for (name, bounds) in &targets.symbol_bounds {
+        if !names.contains(name) {
+            return Err(format!("no symbol named {} on the reels", name));
+        }
+        if bounds.min > bounds.max {
+            return Err(format!("{} has a minimum weight above its maximum", name));
+        }
+    }
+    if targets.bounds.min > targets.bounds.max {
+        return Err("weights need a minimum no higher than the maximum".to_string());
+    }
+    if targets.spins == 0 {
+        return Err("the solver needs at least 1 spin per measurement".to_string());
+    }
+
+    let config = SimulationConfig { spins: targets.spins, bet: 1.0, seed: SIMULATION_SEED };
+    let measure = |machine: &SlotMachine| {
+        let report = simulate(machine, &config);
+        (report.rtp, report.hit_frequency)
+    };
+    let build = |weights: &[f32]| {
+        variables.iter().zip(weights).fold(machine.clone(), |machine, ((reel, name), &weight)| match reel {
+            Some(reel) => machine.with_reel_weight(*reel, name, weight),
+            None => machine.with_symbol_chance(name, weight),
+        })
+    };
+
+    // Start from the current weights, pulled inside their bounds
+    let bounds: Vec<WeightBounds> = variables.iter().map(|(_, name)| targets.bounds_for(name)).collect();
+    let mut weights: Vec<f32> = before.iter().zip(&bounds)
+        .map(|(&chance, bounds)| chance.clamp(bounds.min, bounds.max))
+        .collect();
+    let (mut rtp, mut hit_frequency) = measure(&build(&weights));
+    let mut error = targets.error(rtp, hit_frequency);
+    let mut evaluations = 1;
+    let mut factor = START_FACTOR;
+
+    'search: while factor > MIN_FACTOR && !targets.is_met(rtp, hit_frequency) {
+        // Try every weight up and down by the factor and take the move that helps most
+        let mut best: Option<(Vec<f32>, f64, f64, f64)> = None;
+        for index in 0..weights.len() {
+            for scale in [factor, 1.0 / factor] {
+                if evaluations >= targets.max_evaluations {
+                    break 'search;
+                }
+                let chance = (weights[index] * scale).clamp(bounds[index].min, bounds[index].max);
+                if chance == weights[index] {
+                    continue;
+                }
+
+                let mut candidate = weights.clone();
+                candidate[index] = chance;
+                let (candidate_rtp, candidate_hit_frequency) = measure(&build(&candidate));
+                evaluations += 1;
+
+                let candidate_error = targets.error(candidate_rtp, candidate_hit_frequency);
+                if candidate_error < best.as_ref().map_or(error, |best| best.3) {
+                    best = Some((candidate, candidate_rtp, candidate_hit_frequency, candidate_error));
+                }
+            }
+        }
+
+        match best {
+            Some(best) => (weights, rtp, hit_frequency, error) = best,
+            None => factor = factor.sqrt(),
+        }
+    }
+
+    Ok(SolverResult {
+        machine: build(&weights),
+        weights: variables.into_iter().zip(before).zip(&weights)
+            .map(|(((reel, name), before), &after)| SolvedWeight { name, reel, before, after })
+            .collect(),
+        rtp,
+        hit_frequency,
+        converged: targets.is_met(rtp, hit_frequency),
+        evaluations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::fixtures::coin_flip_machine;
+
+    fn targets(rtp: f64, bounds: WeightBounds) -> SolverTargets {
+        SolverTargets {
+            rtp,
+            hit_frequency: None,
+            tolerance: 0.03,
+            bounds,
+            symbol_bounds: Vec::new(),
+            per_reel: false,
+            max_evaluations: 200,
+            spins: 10_000,
+        }
+    }
+
+    #[test]
+    fn converges_on_the_target_rtp() {
+        let result = solve(&coin_flip_machine(), &targets(1.5, WeightBounds { min: 0.1, max: 10.0 })).unwrap();
+
+        assert!(result.converged);
+        assert!((result.rtp - 1.5).abs() <= 0.03);
+        // The measurement is seeded, so the solved machine measures the same again
+        let report = simulate(&result.machine, &SimulationConfig { spins: 10_000, bet: 1.0, seed: SIMULATION_SEED });
+        assert_eq!(report.rtp, result.rtp);
+    }
+
+    #[test]
+    fn weights_never_reach_zero() {
+        let bounds = WeightBounds { min: 0.0, max: 0.0 };
+        let result = solve(&coin_flip_machine(), &targets(0.0, bounds)).unwrap();
+
+        assert!(result.weights.iter().all(|weight| weight.after == MIN_WEIGHT));
+        assert!(result.machine.reels().iter().flat_map(|reel| reel.strip()).all(|stop| stop.weight > 0.0));
+    }
+
+    #[test]
+    fn rejects_bounds_for_symbols_not_on_the_reels() {
+        let mut targets = targets(0.95, WeightBounds { min: 0.1, max: 10.0 });
+        targets.symbol_bounds.push(("Plum".to_string(), WeightBounds { min: 1.0, max: 2.0 }));
+        assert!(solve(&coin_flip_machine(), &targets).is_err());
+    }
+}
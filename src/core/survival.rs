@@ -0,0 +1,236 @@
+// Goal-and-bankroll survival analysis for the progression mode: start with a pool, get it up to
+// a goal within a budget of rolls, then chase the next, bigger goal with whatever is left. Plays
+// many runs through the same rounds slot-sim plays (see simulation::play_round: free spins at
+// the locked in bet and multiplier, hold & spin rounds to the last respin, sticky wilds carried
+// between spins) to estimate how far players get. Jackpots, gambles, holds and nudges aren't played
+
+use std::fmt;
+use serde::Serialize;
+use crate::entities::slot_machine::{SlotMachine, StickyWild};
+use crate::core::rng::GameRng;
+use crate::core::simulation::play_round;
+
+// Lowest bet the bet controls allow, a pool below it can't roll again
+pub const MIN_BET: f32 = 1.0;
+
+/// The pool needed to clear each level, levels counted from 0
+#[derive(Debug, Clone, Serialize)]
+pub enum GoalCurve {
+    Linear { first: f32, step: f32 }, // first, first + step, first + 2 * step, ...
+    Exponential { first: f32, growth: f32 }, // first, first * growth, first * growth^2, ...
+    Custom(Vec<f32>), // exactly these goals, the run is won after the last one
+}
+
+impl GoalCurve {
+    pub fn goal(&self, level: usize) -> Option<f32> {
+        match self {
+            GoalCurve::Linear { first, step } => Some(first + step * level as f32),
+            GoalCurve::Exponential { first, growth } => Some(first * growth.powi(level as i32)),
+            GoalCurve::Custom(goals) => goals.get(level).copied(),
+        }
+    }
+}
+
+/// How much a player bets on each roll, clamped between MIN_BET and the pool like the bet
+/// controls do
+#[derive(Debug, Clone, Serialize)]
+pub enum BetStrategy {
+    Flat(f32),
+    PoolFraction(f32), // this share of the pool on every roll
+    Martingale { base: f32, max: f32 }, // double the bet after a loss, back to base after a win
+}
+
+impl BetStrategy {
+    // The bet for the next roll, given the last bet and whether it won
+    fn next_bet(&self, pool: f32, last: Option<(f32, bool)>) -> f32 {
+        let bet = match *self {
+            BetStrategy::Flat(bet) => bet,
+            BetStrategy::PoolFraction(fraction) => pool * fraction,
+            BetStrategy::Martingale { base, max } => match last {
+                Some((bet, false)) => (bet * 2.0).min(max),
+                _ => base,
+            },
+        };
+        bet.clamp(MIN_BET, pool.max(MIN_BET))
+    }
+}
+
+/// One progression run's rules, and how many runs to play
+#[derive(Debug, Clone, Serialize)]
+pub struct SurvivalConfig {
+    pub starting_pool: f32,
+    pub bet: BetStrategy,
+    pub rolls_per_level: u32, // paid rolls, free spins and respins don't use them up
+    pub goals: GoalCurve,
+    pub max_levels: usize, // runs that clear this many levels stop there
+    pub runs: u64,
+    pub seed: u64,
+}
+
+/// How often runs cleared one level
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelSurvival {
+    pub level: usize, // from 1
+    pub goal: f32,
+    pub reached: u64, // runs that started the level
+    pub cleared: u64,
+    pub clear_probability: f64, // chance a run clears this level, from the start of the run
+    pub conditional_probability: f64, // chance of clearing it once it's reached
+    pub average_rolls: f64, // rolls used by the runs that cleared it
+}
+
+/// Results of a survival analysis
+#[derive(Debug, Clone, Serialize)]
+pub struct SurvivalReport {
+    pub config: SurvivalConfig,
+    pub levels: Vec<LevelSurvival>,
+    pub expected_levels: f64, // levels cleared by an average run
+    pub busts: u64, // runs that ended with the pool below the minimum bet
+    pub out_of_rolls: u64, // runs that ended with money left but no rolls
+    pub completed: u64, // runs that cleared every level
+}
+
+pub fn analyze_survival(machine: &SlotMachine, config: &SurvivalConfig) -> SurvivalReport {
+    let mut game_rng = GameRng::from_seed(config.seed);
+    let mut levels: Vec<LevelSurvival> = Vec::new();
+    let mut rolls_to_clear: Vec<u64> = Vec::new();
+    let mut total_cleared = 0u64;
+    let (mut busts, mut out_of_rolls, mut completed) = (0u64, 0u64, 0u64);
+
+    for _ in 0..config.runs {
+        let rng = game_rng.rng();
+        let mut pool = config.starting_pool;
+        let mut sticky: Vec<StickyWild> = Vec::new();
+        let mut last_bet: Option<(f32, bool)> = None;
+        let mut level = 0;
+
+        loop {
+            let Some(goal) = config.goals.goal(level).filter(|_| level < config.max_levels) else {
+                completed += 1;
+                break;
+            };
+            if levels.len() == level {
+                levels.push(LevelSurvival {
+                    level: level + 1,
+                    goal,
+                    reached: 0,
+                    cleared: 0,
+                    clear_probability: 0.0,
+                    conditional_probability: 0.0,
+                    average_rolls: 0.0,
+                });
+                rolls_to_clear.push(0);
+            }
+            levels[level].reached += 1;
+
+            let mut rolls = 0;
+            while pool < goal && rolls < config.rolls_per_level && pool >= MIN_BET {
+                let bet = config.bet.next_bet(pool, last_bet);
+                pool -= bet;
+                rolls += 1;
+                let won = play_round(machine, rng, &mut sticky, bet).total();
+                pool += won;
+                last_bet = Some((bet, won > 0.0));
+            }
+
+            if pool < goal {
+                if pool < MIN_BET {
+                    busts += 1;
+                } else {
+                    out_of_rolls += 1;
+                }
+                break;
+            }
+            levels[level].cleared += 1;
+            rolls_to_clear[level] += rolls as u64;
+            total_cleared += 1;
+            level += 1;
+        }
+    }
+
+    let runs = config.runs.max(1) as f64;
+    for (level, rolls) in levels.iter_mut().zip(rolls_to_clear) {
+        level.clear_probability = level.cleared as f64 / runs;
+        level.conditional_probability = level.cleared as f64 / level.reached.max(1) as f64;
+        level.average_rolls = rolls as f64 / level.cleared.max(1) as f64;
+    }
+
+    SurvivalReport {
+        config: config.clone(),
+        levels,
+        expected_levels: total_cleared as f64 / runs,
+        busts,
+        out_of_rolls,
+        completed,
+    }
+}
+
+impl fmt::Display for SurvivalReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:>5} {:>12} {:>10} {:>12} {:>10}", "Level", "Goal", "Clear", "If reached", "Rolls")?;
+        for level in &self.levels {
+            writeln!(f, "{:>5} {:>12.2} {:>9.3}% {:>11.3}% {:>10.1}",
+                level.level,
+                level.goal,
+                level.clear_probability * 100.0,
+                level.conditional_probability * 100.0,
+                level.average_rolls)?;
+        }
+        writeln!(f, "{}", "-".repeat(53))?;
+        writeln!(f, "Expected levels cleared: {:.3}", self.expected_levels)?;
+        let runs = self.config.runs.max(1) as f64;
+        writeln!(f, "Runs ended bust: {:.3}%, out of rolls: {:.3}%, clearing every level: {:.3}%",
+            self.busts as f64 / runs * 100.0,
+            self.out_of_rolls as f64 / runs * 100.0,
+            self.completed as f64 / runs * 100.0)?;
+        write!(f, "Over {} runs (seed {})", self.config.runs, self.config.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::fixtures::{coin_flip_machine, coin_flip_machine_paying};
+
+    fn config() -> SurvivalConfig {
+        SurvivalConfig {
+            starting_pool: 10.0,
+            bet: BetStrategy::Flat(1.0),
+            rolls_per_level: 100,
+            goals: GoalCurve::Linear { first: 20.0, step: 10.0 },
+            max_levels: 3,
+            runs: 200,
+            seed: 7,
+        }
+    }
+
+    #[test]
+    fn a_machine_that_never_pays_busts_every_run() {
+        let report = analyze_survival(&coin_flip_machine_paying(0.0, 0.0), &config());
+
+        assert_eq!(report.busts, 200);
+        assert_eq!(report.expected_levels, 0.0);
+        assert_eq!(report.levels[0].reached, 200);
+        assert_eq!(report.levels[0].cleared, 0);
+    }
+
+    #[test]
+    fn seeded_runs_repeat_and_every_run_ends_once() {
+        let machine = coin_flip_machine_paying(4.0, 8.0);
+        let first = analyze_survival(&machine, &config());
+        let second = analyze_survival(&machine, &config());
+
+        assert_eq!(first.busts + first.out_of_rolls + first.completed, 200);
+        assert_eq!(first.expected_levels, second.expected_levels);
+        assert_eq!(first.busts, second.busts);
+        assert!(first.levels.windows(2).all(|pair| pair[1].reached == pair[0].cleared));
+        assert!(analyze_survival(&coin_flip_machine(), &config()).busts > 0);
+    }
+
+    #[test]
+    fn goal_curves() {
+        assert_eq!(GoalCurve::Linear { first: 100.0, step: 50.0 }.goal(2), Some(200.0));
+        assert_eq!(GoalCurve::Exponential { first: 100.0, growth: 2.0 }.goal(3), Some(800.0));
+        assert_eq!(GoalCurve::Custom(vec![10.0, 20.0]).goal(2), None);
+    }
+}
@@ -0,0 +1,353 @@
+// Machine definition checks, run before a machine is played so a bad file is reported up front
+// instead of panicking mid-spin (a reel with no weight can't be spun, an empty one has no window)
+
+use std::fmt;
+use crate::entities::slot_machine::{EvaluationMode, Reel, SlotMachine, Symbol, SymbolKind};
+use crate::util::textures::SYMBOL_TEXTURES;
+
+/// A problem found in a machine definition, reels and paylines are numbered from 1
+#[derive(Debug, Clone, PartialEq)]
+pub enum MachineError {
+    NoReels,
+    NoRows,
+    EmptyReel { reel: usize },
+    InvalidWeight { reel: usize, symbol: String, weight: f32 }, // negative, NaN or infinite
+    NoWeight { reel: usize }, // every stop weighs 0 so the reel can't land anywhere
+    InvalidMegaways { min_rows: usize, max_rows: usize },
+    DuplicateSymbol { name: String }, // different symbols sharing one name
+    PaylineOutOfBounds { line: usize, row: usize, col: usize },
+    ShortPayline { line: usize, positions: usize, reels: usize }, // doesn't cross every reel
+    InvalidClusterSize { min_size: usize, cells: usize }, // 0, or more cells than the grid has
+    InvalidCoinWeight { value: f32, weight: f32 }, // 0, negative, NaN or infinite
+    MissingTexture { symbol: String }, // shown with its icon instead
+    MissingArtwork { symbol: String }, // no texture and no icon, the symbol is invisible
+    UnreachablePay { symbol: String, count: usize, most: usize },
+    UnreachableTrigger { feature: String, needed: usize, most: usize },
+}
+
+impl MachineError {
+    // Warnings leave the machine playable, errors would crash it or break the display
+    pub fn is_warning(&self) -> bool {
+        matches!(self,
+            MachineError::MissingTexture { .. }
+            | MachineError::UnreachablePay { .. }
+            | MachineError::UnreachableTrigger { .. })
+    }
+}
+
+impl fmt::Display for MachineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MachineError::NoReels => write!(f, "the machine has no reels"),
+            MachineError::NoRows => write!(f, "the machine shows 0 rows"),
+            MachineError::EmptyReel { reel } => write!(f, "reel {} has no stops", reel),
+            MachineError::InvalidWeight { reel, symbol, weight } =>
+                write!(f, "reel {}: {} has weight {}, weights must be 0 or more", reel, symbol, weight),
+            MachineError::NoWeight { reel } => write!(f, "reel {}: every stop has weight 0", reel),
+            MachineError::InvalidMegaways { min_rows, max_rows } =>
+                write!(f, "megaways rows {}-{} need at least 1 row and a minimum no higher than the maximum", min_rows, max_rows),
+            MachineError::DuplicateSymbol { name } => write!(f, "more than one symbol is named {}", name),
+            MachineError::PaylineOutOfBounds { line, row, col } =>
+                write!(f, "payline {} uses row {} on reel {}, outside the grid", line, row + 1, col + 1),
+            MachineError::ShortPayline { line, positions, reels } =>
+                write!(f, "payline {} has {} positions but the machine has {} reels", line, positions, reels),
+            MachineError::InvalidClusterSize { min_size, cells } =>
+                write!(f, "cluster minimum size {} must be between 1 and the grid's {} cells", min_size, cells),
+            MachineError::InvalidCoinWeight { value, weight } =>
+                write!(f, "hold & spin coin value {} has weight {}, coin weights must be more than 0", value, weight),
+            MachineError::MissingTexture { symbol } => write!(f, "{} has no texture, its icon is shown instead", symbol),
+            MachineError::MissingArtwork { symbol } => write!(f, "{} has no texture and no icon", symbol),
+            MachineError::UnreachablePay { symbol, count, most } =>
+                write!(f, "{} pays for {} but at most {} can land together", symbol, count, most),
+            MachineError::UnreachableTrigger { feature, needed, most } =>
+                write!(f, "{} needs {} symbols but at most {} can land", feature, needed, most),
+        }
+    }
+}
+
+// Every problem with the machine, errors and warnings alike. `textures` names the symbols the
+// game has a texture for (see util::textures::SYMBOL_TEXTURES)
+pub fn validate_machine(machine: &SlotMachine, textures: &[&str]) -> Vec<MachineError> {
+    let mut errors = Vec::new();
+    if machine.reels().is_empty() {
+        errors.push(MachineError::NoReels);
+    }
+    if machine.rows() == 0 {
+        errors.push(MachineError::NoRows);
+    }
+    if let Some(config) = machine.megaways().filter(|config| config.min_rows == 0 || config.min_rows > config.max_rows) {
+        errors.push(MachineError::InvalidMegaways { min_rows: config.min_rows, max_rows: config.max_rows });
+    }
+
+    // Reel weights, WeightedIndex refuses negative, non-finite and all-zero weights
+    for (index, reel) in machine.reels().iter().enumerate() {
+        let reel_number = index + 1;
+        if reel.is_empty() {
+            errors.push(MachineError::EmptyReel { reel: reel_number });
+            continue;
+        }
+        let mut valid = true;
+        for stop in reel.strip() {
+            if !stop.weight.is_finite() || stop.weight < 0.0 {
+                errors.push(MachineError::InvalidWeight {
+                    reel: reel_number,
+                    symbol: stop.symbol.name.clone(),
+                    weight: stop.weight,
+                });
+                valid = false;
+            }
+        }
+        if valid && reel.strip().iter().all(|stop| stop.weight == 0.0) {
+            errors.push(MachineError::NoWeight { reel: reel_number });
+        }
+    }
+
+    // One definition per name, the same symbol on several reels or stops is fine
+    let mut symbols: Vec<&Symbol> = Vec::new();
+    for stop in machine.reels().iter().flat_map(|reel| reel.strip()) {
+        let symbol = &stop.symbol;
+        match symbols.iter().find(|known| known.name == symbol.name) {
+            Some(known) => {
+                let same = known.icon == symbol.icon && known.pays == symbol.pays && known.kind == symbol.kind;
+                let error = MachineError::DuplicateSymbol { name: symbol.name.clone() };
+                if !same && !errors.contains(&error) {
+                    errors.push(error);
+                }
+            }
+            None => symbols.push(symbol),
+        }
+    }
+
+    match *machine.evaluation() {
+        EvaluationMode::Lines => {
+            for (index, payline) in machine.paylines().iter().enumerate() {
+                if payline.rows.len() < machine.cols() {
+                    errors.push(MachineError::ShortPayline { line: index + 1, positions: payline.rows.len(), reels: machine.cols() });
+                }
+                for (row, col) in payline.cells() {
+                    if row >= machine.rows() || col >= machine.cols() {
+                        errors.push(MachineError::PaylineOutOfBounds { line: index + 1, row, col });
+                    }
+                }
+            }
+        }
+        EvaluationMode::Cluster { min_size } => {
+            let cells = machine.cols() * machine.rows();
+            if min_size == 0 || min_size > cells {
+                errors.push(MachineError::InvalidClusterSize { min_size, cells });
+            }
+        }
+        EvaluationMode::Ways => {}
+    }
+
+    for symbol in &symbols {
+        if !textures.contains(&symbol.name.as_str()) {
+            if symbol.icon.trim().is_empty() {
+                errors.push(MachineError::MissingArtwork { symbol: symbol.name.clone() });
+            } else {
+                errors.push(MachineError::MissingTexture { symbol: symbol.name.clone() });
+            }
+        }
+
+        let most = most_landed(machine, symbol);
+        for &count in symbol.pays.keys().filter(|&&count| count > most) {
+            errors.push(MachineError::UnreachablePay { symbol: symbol.name.clone(), count, most });
+        }
+    }
+
+    // Features triggered by a count of symbols anywhere on the grid
+    let cells_with = |is_trigger: fn(&Symbol) -> bool| -> usize {
+        machine.reels().iter().map(|reel| most_in_window(reel, machine.rows(), is_trigger)).sum()
+    };
+    if let Some(config) = machine.free_spins() {
+        let most = cells_with(Symbol::is_scatter);
+        if config.trigger_count > most {
+            errors.push(MachineError::UnreachableTrigger { feature: "Free spins".to_string(), needed: config.trigger_count, most });
+        }
+    }
+    if let Some(config) = machine.hold_and_spin() {
+        for &(value, weight) in config.coin_values.iter().filter(|&&(_, weight)| !weight.is_finite() || weight <= 0.0) {
+            errors.push(MachineError::InvalidCoinWeight { value, weight });
+        }
+        let most = cells_with(Symbol::is_coin);
+        if config.trigger_count > most {
+            errors.push(MachineError::UnreachableTrigger { feature: "Hold & spin".to_string(), needed: config.trigger_count, most });
+        }
+    }
+
+    errors
+}
+
+// Validate a machine about to be played and exit with status 2 if it has errors, listing them.
+// The warnings are returned for the caller to mention
+pub fn validate_or_exit(machine: &SlotMachine) -> Vec<MachineError> {
+    let (errors, warnings): (Vec<_>, Vec<_>) = validate_machine(machine, &SYMBOL_TEXTURES.map(|(name, _)| name))
+        .into_iter()
+        .partition(|problem| !problem.is_warning());
+    if !errors.is_empty() {
+        for error in &errors {
+            eprintln!("❌ {}", error);
+        }
+        eprintln!("The machine can't be played, fix the errors above");
+        std::process::exit(2);
+    }
+    warnings
+}
+
+// The highest count a symbol can be paid for: reels in a row from the left that can show it on
+// lines and ways, cells that can show it at once for scatters and clusters. Wilds standing in and
+// mystery symbols revealing as it count too. Cascades drop in more of the strip than a window
+// shows, so a cluster can then use every stop showing it, up to the reel's height
+fn most_landed(machine: &SlotMachine, symbol: &Symbol) -> usize {
+    let shows = |candidate: &Symbol| candidate.matches(symbol)
+        || (candidate.is_mystery() && symbol.kind == SymbolKind::Regular);
+
+    if symbol.is_scatter() {
+        return machine.reels().iter().map(|reel| most_in_window(reel, machine.rows(), shows)).sum();
+    }
+    if matches!(machine.evaluation(), EvaluationMode::Cluster { .. }) {
+        return machine.reels().iter()
+            .map(|reel| {
+                let landed = most_in_window(reel, machine.rows(), shows);
+                match machine.cascade() {
+                    Some(_) => landed.max(reel.strip().iter().filter(|stop| shows(&stop.symbol)).count().min(machine.rows())),
+                    None => landed,
+                }
+            })
+            .sum();
+    }
+    machine.reels().iter()
+        .take_while(|reel| reel.strip().iter().any(|stop| stop.weight > 0.0 && shows(&stop.symbol)))
+        .count()
+}
+
+// The most cells of one reel showing a symbol at once: the best window of `rows` stops on the
+// strip, starting from any stop the reel can land on
+fn most_in_window(reel: &Reel, rows: usize, shows: impl Fn(&Symbol) -> bool) -> usize {
+    reel.strip().iter()
+        .enumerate()
+        .filter(|(_, stop)| stop.weight > 0.0)
+        .map(|(stop, _)| reel.window(stop, rows).iter().filter(|&symbol| shows(symbol)).count())
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::slot_machine::{CascadeConfig, HoldAndSpinConfig, FreeSpinsConfig, MegawaysConfig, Payline, ReelStop};
+
+    fn cherry() -> Symbol {
+        Symbol::new("🍒", "Cherry", 2.0, 0.0, 1.0)
+    }
+
+    fn three_reels(symbol: Symbol) -> SlotMachine {
+        let reel = Reel::new(vec![symbol]);
+        SlotMachine::new(vec![reel.clone(), reel.clone(), reel])
+    }
+
+    fn problems(machine: &SlotMachine) -> Vec<MachineError> {
+        validate_machine(machine, &SYMBOL_TEXTURES.map(|(name, _)| name))
+    }
+
+    #[test]
+    fn every_preset_is_playable() {
+        for name in SlotMachine::PRESETS {
+            let machine = SlotMachine::preset(name).unwrap();
+            let errors: Vec<_> = problems(&machine).into_iter().filter(|problem| !problem.is_warning()).collect();
+            assert!(errors.is_empty(), "{}: {:?}", name, errors);
+        }
+        assert!(problems(&SlotMachine::default()).is_empty());
+    }
+
+    #[test]
+    fn reel_errors() {
+        assert!(problems(&SlotMachine::new(Vec::new())).contains(&MachineError::NoReels));
+        assert!(problems(&three_reels(cherry()).with_rows(0)).contains(&MachineError::NoRows));
+
+        let machine = SlotMachine::new(vec![Reel::new(vec![cherry()]), Reel::new(Vec::new()), Reel::new(vec![cherry()])]);
+        assert!(problems(&machine).contains(&MachineError::EmptyReel { reel: 2 }));
+
+        let machine = three_reels(cherry()).with_reel_weight(0, "Cherry", -1.0);
+        assert!(problems(&machine).contains(&MachineError::InvalidWeight { reel: 1, symbol: "Cherry".to_string(), weight: -1.0 }));
+
+        let machine = three_reels(cherry()).with_reel_weight(2, "Cherry", 0.0);
+        assert!(problems(&machine).contains(&MachineError::NoWeight { reel: 3 }));
+
+        let machine = three_reels(cherry()).with_megaways(MegawaysConfig { min_rows: 4, max_rows: 3 });
+        assert!(problems(&machine).contains(&MachineError::InvalidMegaways { min_rows: 4, max_rows: 3 }));
+    }
+
+    #[test]
+    fn duplicate_symbols() {
+        let other = Symbol::new("🍒", "Cherry", 5.0, 0.0, 1.0);
+        let machine = SlotMachine::new(vec![Reel::new(vec![cherry()]), Reel::new(vec![other]), Reel::new(vec![cherry()])]);
+        assert_eq!(problems(&machine), vec![MachineError::DuplicateSymbol { name: "Cherry".to_string() }]);
+    }
+
+    #[test]
+    fn evaluation_errors() {
+        let machine = three_reels(cherry()).with_paylines(vec![Payline::new(&[1, 3, 1])]);
+        assert!(problems(&machine).contains(&MachineError::PaylineOutOfBounds { line: 1, row: 3, col: 1 }));
+
+        let machine = three_reels(cherry()).with_paylines(vec![Payline::new(&[1, 1])]);
+        assert!(problems(&machine).contains(&MachineError::ShortPayline { line: 1, positions: 2, reels: 3 }));
+
+        for min_size in [0, 10] {
+            let machine = three_reels(cherry()).with_evaluation(EvaluationMode::Cluster { min_size });
+            assert!(problems(&machine).contains(&MachineError::InvalidClusterSize { min_size, cells: 9 }));
+        }
+    }
+
+    #[test]
+    fn feature_errors() {
+        let machine = three_reels(cherry()).with_hold_and_spin(HoldAndSpinConfig {
+            trigger_count: 3,
+            coin_values: vec![(1.0, 1.0), (5.0, 0.0)],
+            ..HoldAndSpinConfig::default()
+        });
+        let found = problems(&machine);
+        assert!(found.contains(&MachineError::InvalidCoinWeight { value: 5.0, weight: 0.0 }));
+        assert!(found.contains(&MachineError::UnreachableTrigger { feature: "Hold & spin".to_string(), needed: 3, most: 0 }));
+    }
+
+    #[test]
+    fn triggers_count_what_a_window_can_show() {
+        // One scatter in every three stops, a window of three rows never shows two from a reel
+        let scatter = Symbol::new("💎", "Scatter", 1.0, 0.0, 1.0).with_pays(&[(3, 2.0), (4, 5.0)]).with_kind(SymbolKind::Scatter);
+        let reel = Reel::new(vec![scatter, Symbol { chance: 2.0, ..cherry() }]);
+        let machine = SlotMachine::new(vec![reel.clone(), reel.clone(), reel])
+            .with_free_spins(FreeSpinsConfig { trigger_count: 4, ..FreeSpinsConfig::default() });
+        let found = problems(&machine);
+
+        assert!(found.contains(&MachineError::UnreachableTrigger { feature: "Free spins".to_string(), needed: 4, most: 3 }));
+        assert!(found.contains(&MachineError::UnreachablePay { symbol: "Scatter".to_string(), count: 4, most: 3 }));
+
+        // Cherries alternate with blanks, so two rows only ever show one, until cascades drop in more
+        let cherry = cherry().with_pays(&[(6, 1.0)]);
+        let blank = Symbol::new("🔔", "Bell", 1.0, 0.0, 1.0);
+        let reel = Reel::from_strip([&cherry, &blank, &cherry, &blank].map(|symbol| ReelStop::new(symbol.clone(), 1.0)).to_vec());
+        let machine = SlotMachine::new(vec![reel.clone(), reel.clone(), reel])
+            .with_rows(2)
+            .with_evaluation(EvaluationMode::Cluster { min_size: 3 });
+        let unreachable = MachineError::UnreachablePay { symbol: "Cherry".to_string(), count: 6, most: 3 };
+        assert!(problems(&machine).contains(&unreachable));
+        assert!(!problems(&machine.with_cascades(CascadeConfig::default())).contains(&unreachable));
+    }
+
+    #[test]
+    fn warnings() {
+        let machine = three_reels(Symbol::new("🍑", "Peach", 2.0, 0.0, 1.0).with_pays(&[(4, 5.0)]))
+            .with_free_spins(FreeSpinsConfig::default());
+        let found = problems(&machine);
+        assert!(found.contains(&MachineError::MissingTexture { symbol: "Peach".to_string() }));
+        assert!(found.contains(&MachineError::UnreachablePay { symbol: "Peach".to_string(), count: 4, most: 3 }));
+        assert!(found.contains(&MachineError::UnreachableTrigger { feature: "Free spins".to_string(), needed: 3, most: 0 }));
+        assert!(found.iter().all(MachineError::is_warning));
+
+        let machine = three_reels(Symbol::new(" ", "Ghost", 2.0, 0.0, 1.0));
+        let found = problems(&machine);
+        assert_eq!(found, vec![MachineError::MissingArtwork { symbol: "Ghost".to_string() }]);
+        assert!(!found[0].is_warning());
+    }
+}
@@ -0,0 +1,20 @@
+// Small machines the unit tests can work out by hand
+
+use crate::entities::slot_machine::{Payline, Reel, ReelStop, SlotMachine, Symbol};
+
+// Three one-row reels of a Cherry and a Lemon, equally weighted, on a single line. Each line
+// lands 1 spin in 8, paying `cherry` and `lemon` times the bet
+pub fn coin_flip_machine_paying(cherry: f32, lemon: f32) -> SlotMachine {
+    let reel = Reel::from_strip(vec![
+        ReelStop::new(Symbol::new("🍒", "Cherry", cherry, 0.0, 1.0), 1.0),
+        ReelStop::new(Symbol::new("🍋", "Lemon", lemon, 0.0, 1.0), 1.0),
+    ]);
+    SlotMachine::new(vec![reel.clone(), reel.clone(), reel])
+        .with_rows(1)
+        .with_paylines(vec![Payline::new(&[0, 0, 0])])
+}
+
+// Cherry lines pay 2 and Lemon lines 4: 75% RTP, 25% hit frequency
+pub fn coin_flip_machine() -> SlotMachine {
+    coin_flip_machine_paying(2.0, 4.0)
+}
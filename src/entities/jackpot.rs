@@ -0,0 +1,166 @@
+use serde::{Serialize, Deserialize};
+use crate::entities::slot_machine::{EvaluationMode, Payline, SlotMachine, Symbol, SymbolKind};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum JackpotTier {
+    Mini,
+    Minor,
+    Major,
+    Grand,
+}
+
+impl JackpotTier {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JackpotTier::Mini => "MINI",
+            JackpotTier::Minor => "MINOR",
+            JackpotTier::Major => "MAJOR",
+            JackpotTier::Grand => "GRAND",
+        }
+    }
+}
+
+/// What has to land to win a jackpot tier
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum JackpotTrigger {
+    Line { payline: usize, symbol: String }, // the symbol on every cell of one payline (e.g. three Sevens on the middle line)
+    AnyLine { symbol: String }, // the symbol on every cell of any payline
+    Count { symbol: String, count: usize }, // a dedicated jackpot symbol landing at least `count` times anywhere
+}
+
+impl JackpotTrigger {
+    pub fn is_hit(&self, grid: &[Vec<Symbol>], paylines: &[Payline]) -> bool {
+        match self {
+            JackpotTrigger::Line { payline, symbol } => paylines.get(*payline)
+                .is_some_and(|line| line_is_all(grid, line, symbol)),
+            JackpotTrigger::AnyLine { symbol } => paylines.iter()
+                .any(|line| line_is_all(grid, line, symbol)),
+            JackpotTrigger::Count { symbol, count } => {
+                grid.iter().flatten().filter(|s| &s.name == symbol).count() >= *count
+            }
+        }
+    }
+}
+
+fn line_is_all(grid: &[Vec<Symbol>], payline: &Payline, symbol: &str) -> bool {
+    payline.cells().iter().all(|&(row, col)| {
+        grid.get(row).and_then(|r| r.get(col)).is_some_and(|s| s.name == symbol)
+    })
+}
+
+/// One progressive pool
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JackpotLevel {
+    pub tier: JackpotTier,
+    pub seed: f32, // the pool resets to this after being won
+    pub contribution_rate: f32, // share of every bet added to the pool (0.005 = 0.5%)
+    pub trigger: JackpotTrigger,
+    pub value: f32,
+}
+
+impl JackpotLevel {
+    pub fn new(tier: JackpotTier, seed: f32, contribution_rate: f32, trigger: JackpotTrigger) -> Self {
+        Self { tier, seed, contribution_rate, trigger, value: seed }
+    }
+}
+
+/// Progressive jackpot pools for one machine, saved to jackpot.json under the machine's name so
+/// they keep growing across sessions
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "ui", derive(bevy::prelude::Resource))]
+pub struct Jackpot {
+    #[serde(skip)]
+    machine: String, // the key the pools are saved under
+    levels: Vec<JackpotLevel>,
+}
+
+impl Jackpot {
+    pub fn new(levels: Vec<JackpotLevel>) -> Self {
+        Self { machine: String::new(), levels }
+    }
+
+    pub fn with_machine(mut self, machine: &str) -> Self {
+        self.machine = machine.to_string();
+        self
+    }
+
+    pub fn machine(&self) -> &str {
+        &self.machine
+    }
+
+    pub fn levels(&self) -> &[JackpotLevel] {
+        &self.levels
+    }
+
+    pub fn value(&self, tier: JackpotTier) -> f32 {
+        self.levels.iter().find(|level| level.tier == tier).map_or(0.0, |level| level.value)
+    }
+
+    // Every paid bet feeds every pool by its own rate
+    pub fn contribute(&mut self, bet: f32) {
+        for level in &mut self.levels {
+            level.value += bet * level.contribution_rate;
+        }
+    }
+
+    // The highest tier whose trigger landed on this grid
+    pub fn check(&self, grid: &[Vec<Symbol>], paylines: &[Payline]) -> Option<JackpotTier> {
+        self.levels.iter()
+            .filter(|level| level.trigger.is_hit(grid, paylines))
+            .map(|level| level.tier)
+            .max()
+    }
+
+    // Pay out a tier and reset it to its seed value
+    pub fn award(&mut self, tier: JackpotTier) -> f32 {
+        match self.levels.iter_mut().find(|level| level.tier == tier) {
+            Some(level) => {
+                let won = level.value;
+                level.value = level.seed;
+                won
+            }
+            None => 0.0,
+        }
+    }
+}
+
+impl Jackpot {
+    // Tiers fitted to the machine's layout, won by its two best paying symbols. Line machines use
+    // their first payline (the middle row on the presets) and any payline, ways and cluster
+    // machines count the symbols across the grid. On the classic 3x3 machine that's 3 Stars
+    // anywhere, Stars or Sevens on the middle line and Sevens on any line
+    pub fn for_machine(machine: &SlotMachine) -> Self {
+        let mut symbols: Vec<&Symbol> = Vec::new();
+        for stop in machine.reels().iter().flat_map(|reel| reel.strip()) {
+            let known = symbols.iter().any(|symbol| symbol.name == stop.symbol.name);
+            if stop.symbol.kind == SymbolKind::Regular && stop.weight > 0.0 && !known {
+                symbols.push(&stop.symbol);
+            }
+        }
+        let top_pay = |symbol: &&Symbol| symbol.pays.values().copied().fold(0.0, f32::max);
+        symbols.sort_by(|a, b| top_pay(b).total_cmp(&top_pay(a)));
+        let top = symbols.first().map_or(String::new(), |symbol| symbol.name.clone());
+        let second = symbols.get(1).map_or(top.clone(), |symbol| symbol.name.clone());
+
+        let cells = machine.cols() * machine.rows();
+        let count = |symbol: &str, count: usize| JackpotTrigger::Count { symbol: symbol.to_string(), count: count.max(1) };
+        let triggers = if *machine.evaluation() == EvaluationMode::Lines && !machine.paylines().is_empty() {
+            [
+                count(&second, cells / 3),
+                JackpotTrigger::Line { payline: 0, symbol: second.clone() },
+                JackpotTrigger::AnyLine { symbol: top.clone() },
+                JackpotTrigger::Line { payline: 0, symbol: top.clone() },
+            ]
+        } else {
+            [count(&second, cells / 3), count(&top, cells / 3), count(&second, cells / 2), count(&top, cells / 2)]
+        };
+
+        let [mini, minor, major, grand] = triggers;
+        Self::new(vec![
+            JackpotLevel::new(JackpotTier::Mini, 10.0, 0.005, mini),
+            JackpotLevel::new(JackpotTier::Minor, 25.0, 0.005, minor),
+            JackpotLevel::new(JackpotTier::Major, 100.0, 0.003, major),
+            JackpotLevel::new(JackpotTier::Grand, 500.0, 0.002, grand),
+        ])
+    }
+}
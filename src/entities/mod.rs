@@ -1,2 +1,6 @@
+pub mod jackpot;
 pub mod player;
-pub mod slot_machine;
\ No newline at end of file
+pub mod slot_machine;
+
+#[cfg(test)]
+pub mod fixtures;
@@ -1,110 +1,1809 @@
 use rand::prelude::*;
 use rand::distributions::WeightedIndex;
-use bevy::prelude::Resource;
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Clone)]
+/// Rules for a wild symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WildRules {
+    pub pays_as_itself: bool, // a run of wilds can pay from the wild's own pay table
+    pub excludes: Vec<String>, // names of symbols this wild won't substitute for
+    pub multiplier: f32, // applied to a line win once for every wild that helped form it
+    pub expanding: bool, // fills its whole reel before the grid is evaluated
+    pub sticky_spins: u32, // stays on its cell for this many following spins (0 for none)
+}
+
+impl Default for WildRules {
+    fn default() -> Self {
+        Self {
+            pays_as_itself: true,
+            excludes: Vec::new(),
+            multiplier: 1.0,
+            expanding: false,
+            sticky_spins: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum SymbolKind {
+    #[default]
+    Regular,
+    Wild(WildRules),
+    Scatter, // pays on count anywhere on the grid, never on lines
+    Coin, // hold-and-spin symbol, locks in place with a cash value and never pays on lines
+    Mystery, // every mystery symbol on the grid turns into the same random regular symbol
+    // Fills the cells below a short reel when a Grid is read row by row, never pays or matches.
+    // Only Grid::to_rows makes it and machine files can't use it, so it's never a landed symbol
+    #[serde(skip)]
+    Padding,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
     pub icon: String,
     pub name: String,
-    pub multiplier: f32,
+    #[serde(default)]
     pub addition: f32,
     pub chance: f32,
+    // Pay table: matches counted from the leftmost reel -> bet multiplier
+    #[serde(default)]
+    pub pays: BTreeMap<usize, f32>,
+    #[serde(default)]
+    pub kind: SymbolKind,
 }
 
+// What a reel without stops shows, and what fills the cells under a short reel
+static PADDING: Symbol = Symbol {
+    icon: String::new(),
+    name: String::new(),
+    addition: 0.0,
+    chance: 0.0,
+    pays: BTreeMap::new(),
+    kind: SymbolKind::Padding,
+};
+
 impl Symbol {
+    // `multiplier` pays for 3 of a kind, use with_pays for 4/5 of a kind on wider machines and
+    // for the cluster sizes a cluster machine pays
     pub fn new(icon: &str, name: &str, multiplier: f32, addition: f32, chance: f32) -> Self {
         Self { 
             icon: icon.to_string(), 
             name: name.to_string(), 
-            multiplier, 
             addition, 
-            chance 
+            chance,
+            pays: BTreeMap::from([(3, multiplier)]),
+            kind: SymbolKind::Regular,
+        }
+    }
+
+    // Placeholder for a cell a reel doesn't show this spin, see Grid::to_rows
+    fn padding() -> Self {
+        PADDING.clone()
+    }
+
+    pub fn with_kind(mut self, kind: SymbolKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn wild_rules(&self) -> Option<&WildRules> {
+        match &self.kind {
+            SymbolKind::Wild(rules) => Some(rules),
+            _ => None,
+        }
+    }
+
+    pub fn is_wild(&self) -> bool {
+        self.wild_rules().is_some()
+    }
+
+    pub fn is_scatter(&self) -> bool {
+        self.kind == SymbolKind::Scatter
+    }
+
+    pub fn is_coin(&self) -> bool {
+        self.kind == SymbolKind::Coin
+    }
+
+    pub fn is_mystery(&self) -> bool {
+        self.kind == SymbolKind::Mystery
+    }
+
+    pub fn is_padding(&self) -> bool {
+        self.kind == SymbolKind::Padding
+    }
+
+    // Whether this symbol can stand in for `other` on a line
+    pub fn matches(&self, other: &Symbol) -> bool {
+        if self.is_padding() || other.is_padding() {
+            return false;
+        }
+        if self.name == other.name {
+            return true;
         }
+        match self.wild_rules() {
+            Some(rules) => !other.is_wild() && !other.is_scatter() && !other.is_coin() && !other.is_mystery() && !rules.excludes.contains(&other.name),
+            None => false,
+        }
+    }
+
+    pub fn with_pays(mut self, pays: &[(usize, f32)]) -> Self {
+        self.pays = pays.iter().copied().collect();
+        self
+    }
+
+    // Multiplier for a run of `count`, using the best entry the run reaches (None if it pays nothing)
+    pub fn pay_for(&self, count: usize) -> Option<f32> {
+        self.pays.range(..=count).next_back().map(|(_, &multiplier)| multiplier)
     }
 }
 
-#[derive(Debug, Clone)]
+/// One position on a reel strip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReelStop {
+    pub symbol: Symbol,
+    pub weight: f32,
+}
+
+impl ReelStop {
+    pub fn new(symbol: Symbol, weight: f32) -> Self {
+        Self { symbol, weight }
+    }
+}
+
+// Longest strip Reel::new lays out, rare symbols still get one stop when a chance table would need more
+const MAX_STRIP_STOPS: usize = 200;
+
+/// A physical reel: an ordered, circular strip of stops. A spin picks one stop and the
+/// visible window is the consecutive stops starting there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reel {
-    symbols: Vec<Symbol>,
+    strip: Vec<ReelStop>,
     // Note: WeightedIndex doesn't implement Clone, so we'll recreate it when needed
 }
 
 impl Reel {
+    // Lay the symbols out as a physical strip: each symbol gets stops in proportion to its
+    // chance (the rarest gets one) and its copies are spread evenly along the strip. Every stop
+    // weighs the same, so a symbol shows on any row of the window as often as its chance says.
+    // Symbols with no chance get a single stop of weight 0
     pub fn new(symbols: Vec<Symbol>) -> Self {
-        Self { symbols }
+        let rarest = symbols.iter()
+            .map(|symbol| symbol.chance)
+            .filter(|&chance| chance > 0.0)
+            .fold(f32::INFINITY, f32::min);
+        let total: f32 = symbols.iter().filter(|symbol| symbol.chance > 0.0).map(|symbol| symbol.chance / rarest).sum();
+        let scale = (MAX_STRIP_STOPS as f32 / total).min(1.0);
+
+        // Copy k of a symbol with n stops sits at (k + 0.5) / n along the strip
+        let mut placed: Vec<(f32, ReelStop)> = Vec::new();
+        for symbol in symbols {
+            let (count, weight) = if symbol.chance > 0.0 {
+                (((symbol.chance / rarest * scale).round() as usize).max(1), 1.0)
+            } else {
+                (1, 0.0)
+            };
+            for copy in 0..count {
+                placed.push(((copy as f32 + 0.5) / count as f32, ReelStop::new(symbol.clone(), weight)));
+            }
+        }
+        placed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { strip: placed.into_iter().map(|(_, stop)| stop).collect() }
+    }
+
+    // Shuffle the stops with a fixed seed, so reels sharing a symbol set don't all put the same
+    // neighbours next to each other
+    pub fn shuffled(mut self, seed: u64) -> Self {
+        self.strip.shuffle(&mut StdRng::seed_from_u64(seed));
+        self
+    }
+
+    // Gather the named symbol's stops into runs of `size` in a row, each run where its first stop
+    // was, so a window can show a stack of them. The strip keeps every stop it had
+    pub fn stacked(self, name: &str, size: usize) -> Self {
+        let positions: Vec<usize> = self.strip.iter()
+            .enumerate()
+            .filter(|(_, stop)| stop.symbol.name == name)
+            .map(|(position, _)| position)
+            .collect();
+        let runs: Vec<&[usize]> = positions.chunks(size.max(1)).collect();
+
+        let mut strip = Vec::with_capacity(self.strip.len());
+        for (position, stop) in self.strip.iter().enumerate() {
+            if stop.symbol.name != name {
+                strip.push(stop.clone());
+            } else if let Some(run) = runs.iter().find(|run| run[0] == position) {
+                strip.extend(run.iter().map(|&member| self.strip[member].clone()));
+            }
+        }
+        Self { strip }
+    }
+
+    pub fn from_strip(strip: Vec<ReelStop>) -> Self {
+        Self { strip }
+    }
+
+    // Reweight every stop showing the named symbol, so one reel can make a symbol rarer or
+    // more common than the others
+    pub fn with_weight(mut self, name: &str, weight: f32) -> Self {
+        for stop in self.strip.iter_mut().filter(|stop| stop.symbol.name == name) {
+            stop.weight = weight;
+        }
+        self
+    }
+
+    // Make the named symbol more or less common: its chance becomes `chance` and every stop
+    // showing it is scaled by the same factor, so a reel carrying it more rarely still does
+    pub fn with_chance(mut self, name: &str, chance: f32) -> Self {
+        for stop in self.strip.iter_mut().filter(|stop| stop.symbol.name == name) {
+            stop.weight = if stop.symbol.chance > 0.0 {
+                stop.weight * chance / stop.symbol.chance
+            } else {
+                chance
+            };
+            stop.symbol.chance = chance;
+        }
+        self
+    }
+
+    // Take the named symbol off this reel entirely
+    pub fn without(mut self, name: &str) -> Self {
+        self.strip.retain(|stop| stop.symbol.name != name);
+        self
+    }
+
+    pub fn strip(&self) -> &[ReelStop] {
+        &self.strip
+    }
+
+    pub fn len(&self) -> usize {
+        self.strip.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strip.is_empty()
+    }
+
+    // Symbol at a strip position, wrapping around since the strip is circular. A reel without
+    // stops (validation rejects it) shows padding rather than panicking
+    pub fn symbol_at(&self, position: usize) -> &Symbol {
+        if self.strip.is_empty() {
+            return &PADDING;
+        }
+        &self.strip[position % self.strip.len()].symbol
+    }
+
+    // The `rows` consecutive symbols shown when the reel lands on `stop`
+    pub fn window(&self, stop: usize, rows: usize) -> Vec<Symbol> {
+        (0..rows).map(|row| self.symbol_at(stop + row).clone()).collect()
     }
 
     pub fn spin(&self) -> Symbol {
-        let mut rng = thread_rng();
-        let weights = self.symbols.iter().map(|s| s.chance).collect::<Vec<_>>();
-        let dist = WeightedIndex::new(weights).unwrap();
-        self.symbols[dist.sample(&mut rng)].clone()
+        self.spin_with_rng(&mut thread_rng())
+    }
+
+    pub fn spin_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Symbol {
+        let stop = self.spin_stop_with_rng(rng);
+        self.symbol_at(stop).clone()
+    }
+
+    // Pick the stop index the reel lands on, using the per-stop weights. Weights WeightedIndex
+    // refuses (all 0, negative, NaN) fall back to every stop being equally likely
+    pub fn spin_stop_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        if self.strip.is_empty() {
+            return 0;
+        }
+        let weights = self.strip.iter().map(|stop| stop.weight).collect::<Vec<_>>();
+        match WeightedIndex::new(weights) {
+            Ok(dist) => dist.sample(rng),
+            Err(_) => rng.gen_range(0..self.strip.len()),
+        }
+    }
+
+    // Stop index after nudging the strip one position, the window moves down the strip by one
+    pub fn nudge(&self, stop: usize) -> usize {
+        (stop + 1) % self.strip.len().max(1)
+    }
+
+    // Stop index one position up the strip, the next stop to drop in above the window
+    pub fn previous(&self, stop: usize) -> usize {
+        match self.strip.len() {
+            0 => 0,
+            len => (stop % len + len - 1) % len,
+        }
+    }
+}
+
+/// The symbols a spin shows, one column per reel from top to bottom. Columns can differ in
+/// height on Megaways machines, `to_rows` gives the row-major view (grid[row][col]) the rest of
+/// the game works with, padded under the shorter reels
+#[derive(Debug, Clone, Default)]
+pub struct Grid {
+    columns: Vec<Vec<Symbol>>,
+}
+
+impl Grid {
+    pub fn from_columns(columns: Vec<Vec<Symbol>>) -> Self {
+        Self { columns }
+    }
+
+    // Read a row-major grid back into columns, dropping the padding
+    pub fn from_rows(rows: &[Vec<Symbol>]) -> Self {
+        let cols = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let columns = (0..cols)
+            .map(|col| rows[..Self::column_height(rows, col)].iter()
+                .map(|row| row[col].clone())
+                .collect())
+            .collect();
+        Self { columns }
+    }
+
+    // How many cells a reel shows in a row-major grid, the rows above the padding to_rows adds
+    pub fn column_height(rows: &[Vec<Symbol>], col: usize) -> usize {
+        rows.iter()
+            .take_while(|row| row.get(col).is_some_and(|symbol| !symbol.is_padding()))
+            .count()
+    }
+
+    pub fn columns(&self) -> &[Vec<Symbol>] {
+        &self.columns
+    }
+
+    // The row-major view, the only place padding is added under the shorter reels
+    pub fn to_rows(&self) -> Vec<Vec<Symbol>> {
+        let rows = self.columns.iter().map(|column| column.len()).max().unwrap_or(0);
+        (0..rows)
+            .map(|row| self.columns.iter()
+                .map(|column| column.get(row).cloned().unwrap_or_else(Symbol::padding))
+                .collect())
+            .collect()
+    }
+}
+
+/// A payline: which row to read on each reel, from left to right
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Payline {
+    pub rows: Vec<usize>,
+}
+
+impl Payline {
+    pub fn new(rows: &[usize]) -> Self {
+        Self { rows: rows.to_vec() }
+    }
+
+    // Grid coordinates (row, col) covered by this line
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        self.rows.iter().enumerate().map(|(col, &row)| (row, col)).collect()
+    }
+
+    // 3x3: three rows and both diagonals
+    pub fn standard_3x3() -> Vec<Payline> {
+        Self::from_table(&[
+            &[1, 1, 1],
+            &[0, 0, 0],
+            &[2, 2, 2],
+            &[0, 1, 2],
+            &[2, 1, 0],
+        ])
+    }
+
+    // 5x3 with 20 lines: rows, V shapes, steps and zigzags
+    pub fn standard_5x3_20() -> Vec<Payline> {
+        Self::from_table(&[
+            &[1, 1, 1, 1, 1],
+            &[0, 0, 0, 0, 0],
+            &[2, 2, 2, 2, 2],
+            &[0, 1, 2, 1, 0],
+            &[2, 1, 0, 1, 2],
+            &[0, 0, 1, 2, 2],
+            &[2, 2, 1, 0, 0],
+            &[1, 0, 0, 0, 1],
+            &[1, 2, 2, 2, 1],
+            &[0, 1, 1, 1, 0],
+            &[2, 1, 1, 1, 2],
+            &[1, 0, 1, 2, 1],
+            &[1, 2, 1, 0, 1],
+            &[0, 1, 0, 1, 0],
+            &[2, 1, 2, 1, 2],
+            &[1, 1, 0, 1, 1],
+            &[1, 1, 2, 1, 1],
+            &[0, 0, 2, 0, 0],
+            &[2, 2, 0, 2, 2],
+            &[0, 2, 2, 2, 0],
+        ])
+    }
+
+    // 5x3 with 25 lines: the 20 line set plus five extra zigzags
+    pub fn standard_5x3_25() -> Vec<Payline> {
+        let mut lines = Self::standard_5x3_20();
+        lines.extend(Self::from_table(&[
+            &[2, 0, 0, 0, 2],
+            &[1, 0, 2, 0, 1],
+            &[0, 2, 0, 2, 0],
+            &[2, 0, 2, 0, 2],
+            &[1, 2, 0, 2, 1],
+        ]));
+        lines
+    }
+
+    fn from_table(table: &[&[usize]]) -> Vec<Payline> {
+        table.iter().map(|rows| Self::new(rows)).collect()
     }
 }
 
-#[derive(Debug, Clone, Resource)]
+/// Free spins awarded by landing scatters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FreeSpinsConfig {
+    pub trigger_count: usize, // scatters needed anywhere on the grid
+    pub spins: u32, // free spins awarded per trigger
+    pub multiplier: f32, // applied to every win during the free spins
+    pub retrigger: bool, // whether landing the trigger again during free spins adds more
+    #[serde(default = "default_max_free_spins")]
+    pub max_spins: u32, // most free spins one round can award in total, so retriggers can't go on forever
+}
+
+fn default_max_free_spins() -> u32 {
+    100
+}
+
+impl Default for FreeSpinsConfig {
+    fn default() -> Self {
+        Self {
+            trigger_count: 3,
+            spins: 10,
+            multiplier: 1.0,
+            retrigger: true,
+            max_spins: default_max_free_spins(),
+        }
+    }
+}
+
+impl FreeSpinsConfig {
+    // Spins a trigger adds to a round that has already awarded `awarded`, cut short at max_spins
+    pub fn capped(&self, awarded: u32, spins: u32) -> u32 {
+        spins.min(self.max_spins.saturating_sub(awarded))
+    }
+}
+
+/// Cascading (tumbling) reels: winning symbols are removed, the rest drop down and the gaps
+/// are refilled from the reel strips until no new win appears
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CascadeConfig {
+    pub multiplier_step: f32, // added to the win multiplier after every cascade (0 to keep it at 1x)
+    pub max_cascades: usize, // safety cap for strips that could keep winning forever
+}
+
+impl Default for CascadeConfig {
+    fn default() -> Self {
+        Self {
+            multiplier_step: 1.0,
+            max_cascades: 20,
+        }
+    }
+}
+
+/// Fruit machine holds and nudges, offered at random after a losing spin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HoldNudgeConfig {
+    pub hold_chance: f32, // chance the player may hold reels for the next spin
+    pub nudge_chance: f32, // chance of being given nudges
+    pub max_nudges: u32, // nudges given range from 1 to this
+}
+
+impl Default for HoldNudgeConfig {
+    fn default() -> Self {
+        Self {
+            hold_chance: 0.25,
+            nudge_chance: 0.2,
+            max_nudges: 3,
+        }
+    }
+}
+
+/// Holds and nudges a losing spin offered the player
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HoldNudgeOffer {
+    pub holds: bool,
+    pub nudges: u32,
+}
+
+/// Hold-and-spin bonus: enough coins lock in place with cash values, then only the
+/// remaining cells respin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HoldAndSpinConfig {
+    pub trigger_count: usize, // coins needed anywhere on the grid
+    pub respins: u32, // respins at the start, reset whenever a new coin lands
+    pub coin_values: Vec<(f32, f32)>, // (cash value in bets, weight) a landing coin can carry
+}
+
+impl Default for HoldAndSpinConfig {
+    fn default() -> Self {
+        Self {
+            trigger_count: 6,
+            respins: 3,
+            coin_values: vec![(1.0, 50.0), (2.0, 25.0), (5.0, 15.0), (10.0, 8.0), (50.0, 2.0)],
+        }
+    }
+}
+
+/// A hold-and-spin round in progress
+#[derive(Debug, Clone)]
+pub struct HoldAndSpinBoard {
+    pub grid: Vec<Vec<Symbol>>,
+    pub locked: Vec<Vec<Option<f32>>>, // [row][col] cash value (in bets) of the coin locked there
+    pub respins_left: u32,
+}
+
+impl HoldAndSpinBoard {
+    pub fn is_locked(&self, row: usize, col: usize) -> bool {
+        self.locked.get(row).and_then(|r| r.get(col)).is_some_and(|value| value.is_some())
+    }
+
+    // Every cell the reels show holds a coin (the padding under short reels doesn't count)
+    pub fn is_full(&self) -> bool {
+        self.grid.iter().zip(&self.locked).all(|(symbols, locked)| {
+            symbols.iter().zip(locked).all(|(symbol, value)| symbol.is_padding() || value.is_some())
+        })
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.respins_left == 0 || self.is_full()
+    }
+
+    // Cash value of every locked coin, in bets
+    pub fn total_value(&self) -> f32 {
+        self.locked.iter().flatten().flatten().sum()
+    }
+}
+
+/// A wild held on its cell for the following spins
+#[derive(Debug, Clone)]
+pub struct StickyWild {
+    pub row: usize,
+    pub col: usize,
+    pub symbol: Symbol,
+    pub spins_left: u32, // spins it still stays for, counting the next one
+}
+
+/// What the post-spin modifier stage changed before the grid was evaluated
+#[derive(Debug, Clone, Default)]
+pub struct GridModifiers {
+    pub sticky: Vec<StickyWild>, // sticky wilds from earlier spins placed on this grid
+    pub expanded: Vec<(usize, usize)>, // cells filled by an expanding wild
+    pub mystery: Vec<(usize, usize)>, // cells that landed a mystery symbol
+    pub revealed: Option<Symbol>, // what the mystery symbols turned into
+}
+
+/// One evaluation step of a spin. A spin without cascades has exactly one step
+#[derive(Debug, Clone)]
+pub struct CascadeStep {
+    pub grid: Vec<Vec<Symbol>>,
+    pub wins: Vec<WinningLine>,
+    pub multiplier: f32, // cascade multiplier applied to this step's wins
+    pub removed: Vec<(usize, usize)>, // cells cleared from the previous step's grid (empty for the first step)
+}
+
+/// Everything a single spin produced, from the reel stops to the last cascade
+#[derive(Debug, Clone)]
+pub struct SpinOutcome {
+    pub stops: Vec<usize>,
+    pub heights: Vec<usize>, // visible rows on each reel, only varies on Megaways machines
+    pub modifiers: GridModifiers,
+    pub steps: Vec<CascadeStep>,
+    pub sticky_wilds: Vec<StickyWild>, // sticky wilds carried into the next spin
+}
+
+impl SpinOutcome {
+    // The grid the reels landed on
+    pub fn initial_grid(&self) -> &[Vec<Symbol>] {
+        &self.steps[0].grid
+    }
+
+    // The grid left once the cascades are over
+    pub fn final_grid(&self) -> &[Vec<Symbol>] {
+        &self.steps[self.steps.len() - 1].grid
+    }
+
+    pub fn has_wins(&self) -> bool {
+        self.steps.iter().any(|step| !step.wins.is_empty())
+    }
+
+    // Ways to win the reels landed on: the product of every reel's height
+    pub fn ways(&self) -> u64 {
+        self.heights.iter().map(|&height| height as u64).product()
+    }
+}
+
+/// Megaways: every spin each reel shows a random number of symbols, so the ways to win change
+/// from spin to spin (up to 117,649 on six reels of 2 to 7)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MegawaysConfig {
+    pub min_rows: usize,
+    pub max_rows: usize,
+}
+
+impl Default for MegawaysConfig {
+    fn default() -> Self {
+        Self {
+            min_rows: 2,
+            max_rows: 7,
+        }
+    }
+}
+
+/// How a machine decides which combinations pay
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum EvaluationMode {
+    #[default]
+    Lines, // the machine's paylines
+    Ways, // any run on adjacent reels from the left, in any row (243 ways on 5x3)
+    Cluster { min_size: usize }, // orthogonally connected groups, paid by size
+}
+
+/// A whole machine definition. Serializes to the JSON the simulator and `--machine` load, where
+/// everything but the reels can be left out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(bevy::prelude::Resource))]
 pub struct SlotMachine {
     reels: Vec<Reel>,
+    #[serde(default = "default_rows")]
+    rows: usize, // visible rows on every reel, the tallest a reel gets on Megaways machines
+    #[serde(default)]
+    megaways: Option<MegawaysConfig>,
+    #[serde(default = "Payline::standard_3x3")]
+    paylines: Vec<Payline>,
+    #[serde(default)]
+    evaluation: EvaluationMode,
+    #[serde(default)]
+    free_spins: Option<FreeSpinsConfig>,
+    #[serde(default)]
+    cascade: Option<CascadeConfig>,
+    #[serde(default)]
+    hold_and_spin: Option<HoldAndSpinConfig>,
+    #[serde(default)]
+    hold_nudge: Option<HoldNudgeConfig>,
+}
+
+fn default_rows() -> usize {
+    3
 }
 
 impl SlotMachine {
+    // Uses 3 rows and the stock 3x3 paylines, call with_rows and with_paylines for other layouts
     pub fn new(reels: Vec<Reel>) -> Self {
-        Self { reels }
+        Self {
+            reels,
+            rows: 3,
+            megaways: None,
+            paylines: Payline::standard_3x3(),
+            evaluation: EvaluationMode::Lines,
+            free_spins: None,
+            cascade: None,
+            hold_and_spin: None,
+            hold_nudge: None,
+        }
     }
 
-    pub fn spin_grid(&self, rows: usize) -> Vec<Vec<Symbol>> {
-        (0..rows)
-            .map(|_| self.reels.iter().map(|reel| reel.spin()).collect())
+    pub fn with_hold_nudge(mut self, config: HoldNudgeConfig) -> Self {
+        self.hold_nudge = Some(config);
+        self
+    }
+
+    pub fn hold_nudge(&self) -> Option<&HoldNudgeConfig> {
+        self.hold_nudge.as_ref()
+    }
+
+    // Roll which holds and nudges to offer after a losing spin
+    pub fn roll_hold_nudge<R: Rng + ?Sized>(&self, rng: &mut R) -> HoldNudgeOffer {
+        let Some(config) = &self.hold_nudge else { return HoldNudgeOffer::default() };
+        let holds = rng.gen_bool(config.hold_chance.clamp(0.0, 1.0) as f64);
+        let nudges = if config.max_nudges > 0 && rng.gen_bool(config.nudge_chance.clamp(0.0, 1.0) as f64) {
+            rng.gen_range(1..=config.max_nudges)
+        } else {
+            0
+        };
+        HoldNudgeOffer { holds, nudges }
+    }
+
+    pub fn with_hold_and_spin(mut self, config: HoldAndSpinConfig) -> Self {
+        self.hold_and_spin = Some(config);
+        self
+    }
+
+    pub fn hold_and_spin(&self) -> Option<&HoldAndSpinConfig> {
+        self.hold_and_spin.as_ref()
+    }
+
+    // Start a hold-and-spin round if enough coins landed, every coin locks with a random value
+    pub fn hold_and_spin_trigger<R: Rng + ?Sized>(&self, rng: &mut R, grid: &[Vec<Symbol>]) -> Option<HoldAndSpinBoard> {
+        let config = self.hold_and_spin.as_ref()?;
+        let coins = grid.iter().flatten().filter(|s| s.is_coin()).count();
+        if coins < config.trigger_count {
+            return None;
+        }
+
+        let locked = grid.iter()
+            .map(|row| row.iter()
+                .map(|symbol| symbol.is_coin().then(|| coin_value(config, rng)))
+                .collect())
+            .collect();
+
+        Some(HoldAndSpinBoard {
+            grid: grid.to_vec(),
+            locked,
+            respins_left: config.respins,
+        })
+    }
+
+    // Respin every unlocked cell on its own. New coins lock in and reset the respin counter.
+    // Returns the cells where coins landed
+    pub fn respin_with_rng<R: Rng + ?Sized>(&self, rng: &mut R, board: &mut HoldAndSpinBoard) -> Vec<(usize, usize)> {
+        let Some(config) = &self.hold_and_spin else { return Vec::new() };
+        let mut new_coins = Vec::new();
+
+        for row in 0..board.grid.len() {
+            for col in 0..board.grid[row].len() {
+                if board.is_locked(row, col) || board.grid[row][col].is_padding() {
+                    continue;
+                }
+                let Some(reel) = self.reels.get(col) else { continue };
+                let symbol = reel.spin_with_rng(rng);
+                if symbol.is_coin() {
+                    board.locked[row][col] = Some(coin_value(config, rng));
+                    new_coins.push((row, col));
+                }
+                board.grid[row][col] = symbol;
+            }
+        }
+
+        if new_coins.is_empty() {
+            board.respins_left = board.respins_left.saturating_sub(1);
+        } else {
+            board.respins_left = config.respins;
+        }
+
+        new_coins
+    }
+
+    pub fn with_cascades(mut self, config: CascadeConfig) -> Self {
+        self.cascade = Some(config);
+        self
+    }
+
+    pub fn cascade(&self) -> Option<&CascadeConfig> {
+        self.cascade.as_ref()
+    }
+
+    pub fn with_evaluation(mut self, evaluation: EvaluationMode) -> Self {
+        self.evaluation = evaluation;
+        self
+    }
+
+    pub fn evaluation(&self) -> &EvaluationMode {
+        &self.evaluation
+    }
+
+    pub fn with_paylines(mut self, paylines: Vec<Payline>) -> Self {
+        self.paylines = paylines;
+        self
+    }
+
+    pub fn with_free_spins(mut self, config: FreeSpinsConfig) -> Self {
+        self.free_spins = Some(config);
+        self
+    }
+
+    pub fn free_spins(&self) -> Option<&FreeSpinsConfig> {
+        self.free_spins.as_ref()
+    }
+
+    // Free spins won by the scatters on this grid (0 if the feature isn't triggered)
+    pub fn free_spins_awarded(&self, grid: &[Vec<Symbol>]) -> u32 {
+        let Some(config) = &self.free_spins else { return 0 };
+        let scatters = grid.iter().flatten().filter(|s| s.is_scatter()).count();
+        if scatters >= config.trigger_count {
+            config.spins
+        } else {
+            0
+        }
+    }
+
+    pub fn reels(&self) -> &[Reel] {
+        &self.reels
+    }
+
+    // Reweight the named symbol on one reel only, see Reel::with_weight
+    pub fn with_reel_weight(mut self, reel: usize, name: &str, weight: f32) -> Self {
+        self.reels = self.reels.into_iter()
+            .enumerate()
+            .map(|(index, strip)| if index == reel { strip.with_weight(name, weight) } else { strip })
+            .collect();
+        self
+    }
+
+    // Reweight the named symbol on every reel, see Reel::with_chance
+    pub fn with_symbol_chance(mut self, name: &str, chance: f32) -> Self {
+        self.reels = self.reels.into_iter().map(|reel| reel.with_chance(name, chance)).collect();
+        self
+    }
+
+    pub fn with_rows(mut self, rows: usize) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    // Visible rows on every reel (the most a reel can show on Megaways machines)
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // Reel heights vary per spin and pay ways, the grid is as tall as the tallest reel can get
+    pub fn with_megaways(mut self, config: MegawaysConfig) -> Self {
+        self.rows = config.max_rows;
+        self.evaluation = EvaluationMode::Ways;
+        self.megaways = Some(config);
+        self
+    }
+
+    pub fn megaways(&self) -> Option<&MegawaysConfig> {
+        self.megaways.as_ref()
+    }
+
+    // How many symbols each reel shows this spin, every reel shows all rows unless it's Megaways
+    pub fn spin_heights_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<usize> {
+        match &self.megaways {
+            Some(config) => self.reels.iter()
+                .map(|_| rng.gen_range(config.min_rows..=config.max_rows.max(config.min_rows)))
+                .collect(),
+            None => vec![self.rows; self.reels.len()],
+        }
+    }
+
+    // Number of reels, one grid column each
+    pub fn cols(&self) -> usize {
+        self.reels.len()
+    }
+
+    pub fn paylines(&self) -> &[Payline] {
+        &self.paylines
+    }
+
+    pub fn spin_grid(&self) -> Grid {
+        self.spin_grid_with_rng(&mut thread_rng())
+    }
+
+    // Same as spin_grid but draws from the given RNG, so seeded spins can be replayed
+    pub fn spin_grid_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Grid {
+        let stops = self.spin_stops_with_rng(rng);
+        let heights = self.spin_heights_with_rng(rng);
+        self.reel_grid_at(&stops, &heights)
+    }
+
+    // One stop index per reel
+    pub fn spin_stops_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<usize> {
+        self.reels.iter().map(|reel| reel.spin_stop_with_rng(rng)).collect()
+    }
+
+    // Spin only the reels that aren't held, held reels keep their previous stop
+    pub fn spin_stops_held_with_rng<R: Rng + ?Sized>(&self, rng: &mut R, previous: &[usize], held: &[bool]) -> Vec<usize> {
+        self.reels.iter()
+            .enumerate()
+            .map(|(i, reel)| match (held.get(i), previous.get(i)) {
+                (Some(true), Some(&stop)) => stop,
+                _ => reel.spin_stop_with_rng(rng),
+            })
             .collect()
     }
 
-    pub fn check_wins<'a>(&self, grid: &'a [Vec<Symbol>]) -> Vec<WinningLine<'a>> {
+    // The stops after nudging one reel by a single position
+    pub fn nudge_stops(&self, stops: &[usize], reel: usize) -> Vec<usize> {
+        let mut stops = stops.to_vec();
+        if let (Some(stop), Some(strip)) = (stops.get_mut(reel), self.reels.get(reel)) {
+            *stop = strip.nudge(*stop);
+        }
+        stops
+    }
+
+    // The window each reel shows for the given stops and heights
+    pub fn reel_grid_at(&self, stops: &[usize], heights: &[usize]) -> Grid {
+        Grid::from_columns(self.reels.iter()
+            .zip(stops)
+            .zip(heights)
+            .map(|((reel, &stop), &height)| reel.window(stop, height))
+            .collect())
+    }
+
+    // Build the visible grid (grid[row][col]) for the given reel stops and heights
+    pub fn grid_at(&self, stops: &[usize], heights: &[usize]) -> Vec<Vec<Symbol>> {
+        self.reel_grid_at(stops, heights).to_rows()
+    }
+
+    // Spin the reels and play out every cascade
+    pub fn spin_outcome_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> SpinOutcome {
+        let stops = self.spin_stops_with_rng(rng);
+        let heights = self.spin_heights_with_rng(rng);
+        let mystery = self.roll_mystery(rng);
+        self.outcome_with_modifiers(stops, heights, &[], mystery.as_ref())
+    }
+
+    // Pick the symbol mystery symbols reveal as this spin. None when the machine has no
+    // mystery symbols
+    pub fn roll_mystery<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Symbol> {
+        let candidates = self.mystery_candidates();
+        let dist = WeightedIndex::new(candidates.iter().map(|&(_, weight)| weight)).ok()?;
+        Some(candidates[dist.sample(rng)].0.clone())
+    }
+
+    // What mystery symbols can reveal as, weighted by how often each regular symbol sits on the
+    // strips of all reels. Empty when the machine has no mystery symbols
+    pub fn mystery_candidates(&self) -> Vec<(&Symbol, f32)> {
+        let strips = || self.reels.iter().flat_map(|reel| reel.strip());
+        if !strips().any(|stop| stop.symbol.is_mystery()) {
+            return Vec::new();
+        }
+
+        let mut candidates: Vec<(&Symbol, f32)> = Vec::new();
+        for stop in strips().filter(|stop| stop.symbol.kind == SymbolKind::Regular) {
+            match candidates.iter_mut().find(|(symbol, _)| symbol.name == stop.symbol.name) {
+                Some((_, weight)) => *weight += stop.weight,
+                None => candidates.push((&stop.symbol, stop.weight)),
+            }
+        }
+        candidates
+    }
+
+    // Evaluate the grid at the given stops, then keep tumbling while cascades produce new wins
+    pub fn outcome_at(&self, stops: Vec<usize>, heights: Vec<usize>) -> SpinOutcome {
+        self.outcome_with_modifiers(stops, heights, &[], None)
+    }
+
+    // Same as outcome_at, with the sticky wilds left over from earlier spins and the symbol
+    // any mystery symbols reveal as
+    pub fn outcome_with_modifiers(&self, stops: Vec<usize>, heights: Vec<usize>, sticky: &[StickyWild], mystery: Option<&Symbol>) -> SpinOutcome {
+        let (grid, modifiers, sticky_wilds) = self.apply_modifiers(self.grid_at(&stops, &heights), sticky, mystery);
+        let wins = self.check_wins(&grid);
+        let mut steps = vec![CascadeStep {
+            grid,
+            wins,
+            multiplier: 1.0,
+            removed: Vec::new(),
+        }];
+
+        if let Some(config) = &self.cascade {
+            // Next strip position to drop in on each reel: the one just above the visible window
+            let mut refill: Vec<usize> = self.reels.iter()
+                .zip(&stops)
+                .map(|(reel, &stop)| reel.previous(stop))
+                .collect();
+
+            while steps.len() <= config.max_cascades {
+                let last = &steps[steps.len() - 1];
+
+                // Scatters stay on the grid, everything else that won is cleared
+                let mut removed: Vec<(usize, usize)> = Vec::new();
+                for win in last.wins.iter().filter(|win| win.line_type != LineType::Scatter) {
+                    for &cell in &win.cells {
+                        if !removed.contains(&cell) {
+                            removed.push(cell);
+                        }
+                    }
+                }
+                if removed.is_empty() {
+                    break;
+                }
+
+                let grid = self.tumble(&last.grid, &removed, &mut refill, mystery);
+                // Scatters were already paid on the first step
+                let wins = self.check_wins(&grid).into_iter()
+                    .filter(|win| win.line_type != LineType::Scatter)
+                    .collect();
+                let multiplier = last.multiplier + config.multiplier_step;
+                steps.push(CascadeStep { grid, wins, multiplier, removed });
+            }
+        }
+
+        SpinOutcome { stops, heights, modifiers, steps, sticky_wilds }
+    }
+
+    // Post-spin modifier stage: mystery symbols reveal, sticky wilds from earlier spins go back
+    // on their cells, new sticky wilds are remembered and expanding wilds fill their reel.
+    // Returns the grid to evaluate, what changed and the sticky wilds for the next spin
+    fn apply_modifiers(&self, mut grid: Vec<Vec<Symbol>>, sticky: &[StickyWild], mystery: Option<&Symbol>) -> (Vec<Vec<Symbol>>, GridModifiers, Vec<StickyWild>) {
+        let mut modifiers = GridModifiers::default();
+        let mut next_sticky = Vec::new();
+
+        if let Some(revealed) = mystery {
+            for (row, symbols) in grid.iter_mut().enumerate() {
+                for (col, symbol) in symbols.iter_mut().enumerate() {
+                    if symbol.is_mystery() {
+                        *symbol = revealed.clone();
+                        modifiers.mystery.push((row, col));
+                    }
+                }
+            }
+            if !modifiers.mystery.is_empty() {
+                modifiers.revealed = Some(revealed.clone());
+            }
+        }
+
+        for wild in sticky {
+            let Some(cell) = grid.get_mut(wild.row).and_then(|row| row.get_mut(wild.col)) else { continue };
+            // A reel that came up shorter this spin can't hold it, but it still counts down
+            if !cell.is_padding() {
+                *cell = wild.symbol.clone();
+                modifiers.sticky.push(wild.clone());
+            }
+            if wild.spins_left > 1 {
+                next_sticky.push(StickyWild { spins_left: wild.spins_left - 1, ..wild.clone() });
+            }
+        }
+
+        for (row, symbols) in grid.iter().enumerate() {
+            for (col, symbol) in symbols.iter().enumerate() {
+                let Some(rules) = symbol.wild_rules() else { continue };
+                let already_sticky = sticky.iter().any(|wild| wild.row == row && wild.col == col);
+                if rules.sticky_spins > 0 && !already_sticky {
+                    next_sticky.push(StickyWild {
+                        row,
+                        col,
+                        symbol: symbol.clone(),
+                        spins_left: rules.sticky_spins,
+                    });
+                }
+            }
+        }
+
+        let cols = grid.first().map_or(0, |row| row.len());
+        for col in 0..cols {
+            let expanding = grid.iter()
+                .map(|row| &row[col])
+                .find(|symbol| symbol.wild_rules().is_some_and(|rules| rules.expanding))
+                .cloned();
+            let Some(wild) = expanding else { continue };
+            for (row, symbols) in grid.iter_mut().enumerate() {
+                if symbols[col].name != wild.name && !symbols[col].is_padding() {
+                    symbols[col] = wild.clone();
+                    modifiers.expanded.push((row, col));
+                }
+            }
+        }
+
+        (grid, modifiers, next_sticky)
+    }
+
+    // Remove the cells, let the remaining symbols fall and drop new ones in from the strips.
+    // Mystery symbols dropping in reveal as the same symbol the spin's others did
+    fn tumble(&self, grid: &[Vec<Symbol>], removed: &[(usize, usize)], refill: &mut [usize], mystery: Option<&Symbol>) -> Vec<Vec<Symbol>> {
+        let mut new_grid = grid.to_vec();
+
+        for (col, reel) in self.reels.iter().enumerate() {
+            // Only the cells the reel shows tumble, the padding under a short reel stays put
+            let height = Grid::column_height(grid, col);
+            let kept: Vec<Symbol> = (0..height)
+                .filter(|&row| !removed.contains(&(row, col)))
+                .map(|row| grid[row][col].clone())
+                .collect();
+
+            // The symbol just above the window lands lowest, the ones above it follow
+            let mut column: Vec<Symbol> = Vec::with_capacity(height);
+            for _ in kept.len()..height {
+                let symbol = reel.symbol_at(refill[col]);
+                column.push(match mystery {
+                    Some(revealed) if symbol.is_mystery() => revealed.clone(),
+                    _ => symbol.clone(),
+                });
+                refill[col] = reel.previous(refill[col]);
+            }
+            column.reverse();
+            column.extend(kept);
+
+            for (row, symbol) in column.into_iter().enumerate() {
+                new_grid[row][col] = symbol;
+            }
+        }
+
+        new_grid
+    }
+
+    pub fn check_wins(&self, grid: &[Vec<Symbol>]) -> Vec<WinningLine> {
+        let mut wins = match self.evaluation {
+            EvaluationMode::Lines => self.line_wins(grid),
+            EvaluationMode::Ways => ways_wins(&Grid::from_rows(grid)),
+            EvaluationMode::Cluster { min_size } => cluster_wins(grid, min_size),
+        };
+
+        wins.extend(scatter_wins(grid));
+
+        wins
+    }
+
+    fn line_wins(&self, grid: &[Vec<Symbol>]) -> Vec<WinningLine> {
         let mut wins = Vec::new();
-        let rows = grid.len();
-        let cols = grid[0].len();
 
-        // Horizontals
-        for row in 0..rows {
-            if grid[row].iter().all(|s| s.name == grid[row][0].name) {
+        for (index, payline) in self.paylines.iter().enumerate() {
+            let cells = payline.cells();
+            
+            // Lines that don't fit this grid never win
+            let symbols: Option<Vec<&Symbol>> = cells.iter()
+                .map(|&(row, col)| grid.get(row).and_then(|r| r.get(col)))
+                .collect();
+            let Some(symbols) = symbols else { continue };
+
+            if let Some((symbol, count, multiplier)) = evaluate_line(&symbols) {
                 wins.push(WinningLine {
-                    symbols: grid[row].iter().collect(),
-                    line_type: LineType::Horizontal(row),
+                    symbols: symbols[..count].iter().map(|s| (*s).clone()).collect(),
+                    line_type: LineType::Payline(index),
+                    cells: cells[..count].to_vec(),
+                    count,
+                    symbol: symbol.clone(),
+                    multiplier,
+                    ways: 1,
                 });
             }
         }
 
-        // Skip vertical wins since reels are circular and this won't happen
+        wins
+    }
+}
+
+// Draw a cash value for a coin that just locked
+fn coin_value<R: Rng + ?Sized>(config: &HoldAndSpinConfig, rng: &mut R) -> f32 {
+    match WeightedIndex::new(config.coin_values.iter().map(|&(_, weight)| weight)) {
+        Ok(dist) => config.coin_values[dist.sample(rng)].0,
+        Err(_) => 1.0,
+    }
+}
+
+// Ways evaluation: for every regular symbol, follow the reels from the left while at least one
+// cell on the reel matches (wilds included). The win pays once per combination of cells, reels
+// of different heights just give more or fewer cells to combine
+fn ways_wins(grid: &Grid) -> Vec<WinningLine> {
+    let mut wins = Vec::new();
+
+    // Each regular symbol on the grid is a candidate, wilds only pay by substituting here
+    let mut candidates: Vec<&Symbol> = Vec::new();
+    for symbol in grid.columns().iter().flatten() {
+        if !symbol.is_wild() && !symbol.is_scatter() && !candidates.iter().any(|c| c.name == symbol.name) {
+            candidates.push(symbol);
+        }
+    }
+
+    for target in candidates {
+        let mut symbols = Vec::new();
+        let mut cells = Vec::new();
+        let mut ways: u32 = 1;
+        let mut weighted_ways = 1.0; // ways counted with wild multipliers
+        let mut count = 0;
+
+        for (col, column) in grid.columns().iter().enumerate() {
+            let matching: Vec<(usize, &Symbol)> = column.iter()
+                .enumerate()
+                .filter(|(_, s)| s.matches(target))
+                .collect();
+            if matching.is_empty() {
+                break;
+            }
+
+            ways *= matching.len() as u32;
+            weighted_ways *= matching.iter()
+                .map(|(_, s)| s.wild_rules().map_or(1.0, |rules| rules.multiplier))
+                .sum::<f32>();
+            for (row, symbol) in matching {
+                symbols.push(symbol.clone());
+                cells.push((row, col));
+            }
+            count += 1;
+        }
 
-        // Diagonals
-        let diag1: Vec<&Symbol> = (0..rows).map(|i| &grid[i][i]).collect();
-        if diag1.iter().all(|&s| s.name == diag1[0].name) {
+        if target.pay_for(count).is_some() {
             wins.push(WinningLine {
-                symbols: diag1,
-                line_type: LineType::Diagonal(0),
+                symbols,
+                line_type: LineType::Ways,
+                cells,
+                count,
+                symbol: target.clone(),
+                multiplier: weighted_ways / ways as f32,
+                ways,
             });
         }
+    }
+
+    wins
+}
+
+// Cluster evaluation: flood fill groups of orthogonally adjacent matching cells.
+// Wilds join any neighbouring cluster (and can be shared by several), the pay table is keyed by size
+fn cluster_wins(grid: &[Vec<Symbol>], min_size: usize) -> Vec<WinningLine> {
+    let mut wins = Vec::new();
+    let mut visited: Vec<Vec<bool>> = grid.iter().map(|row| vec![false; row.len()]).collect();
+
+    for (start_row, row) in grid.iter().enumerate() {
+        for (start_col, target) in row.iter().enumerate() {
+            if visited[start_row][start_col] || target.is_wild() || target.is_scatter() || target.is_padding() {
+                continue;
+            }
+
+            let mut cells = vec![(start_row, start_col)];
+            let mut stack = vec![(start_row, start_col)];
+            visited[start_row][start_col] = true;
+
+            while let Some((r, c)) = stack.pop() {
+                let neighbours = [
+                    (r.wrapping_sub(1), c),
+                    (r + 1, c),
+                    (r, c.wrapping_sub(1)),
+                    (r, c + 1),
+                ];
+                for (nr, nc) in neighbours {
+                    let Some(symbol) = grid.get(nr).and_then(|row| row.get(nc)) else { continue };
+                    if cells.contains(&(nr, nc)) || !symbol.matches(target) {
+                        continue;
+                    }
+                    if !symbol.is_wild() {
+                        visited[nr][nc] = true;
+                    }
+                    cells.push((nr, nc));
+                    stack.push((nr, nc));
+                }
+            }
+
+            let size = cells.len();
+            if size < min_size || target.pay_for(size).is_none() {
+                continue;
+            }
 
-        let diag2: Vec<&Symbol> = (0..rows).map(|i| &grid[i][cols - 1 - i]).collect();
-        if diag2.iter().all(|&s| s.name == diag2[0].name) {
+            let symbols: Vec<Symbol> = cells.iter().map(|&(r, c)| grid[r][c].clone()).collect();
+            let multiplier = symbols.iter()
+                .filter_map(|s| s.wild_rules())
+                .map(|rules| rules.multiplier)
+                .product();
             wins.push(WinningLine {
-                symbols: diag2,
-                line_type: LineType::Diagonal(1),
+                symbols,
+                line_type: LineType::Cluster,
+                cells,
+                count: size,
+                symbol: target.clone(),
+                multiplier,
+                ways: 1,
             });
         }
+    }
 
-        wins
+    wins
+}
+
+// Scatters pay on how many landed anywhere, independent of the paylines
+fn scatter_wins(grid: &[Vec<Symbol>]) -> Vec<WinningLine> {
+    let mut wins: Vec<WinningLine> = Vec::new();
+
+    for (row, symbols) in grid.iter().enumerate() {
+        for (col, symbol) in symbols.iter().enumerate() {
+            if !symbol.is_scatter() {
+                continue;
+            }
+            match wins.iter_mut().find(|win| win.symbol.name == symbol.name) {
+                Some(win) => {
+                    win.symbols.push(symbol.clone());
+                    win.cells.push((row, col));
+                    win.count += 1;
+                }
+                None => wins.push(WinningLine {
+                    symbols: vec![symbol.clone()],
+                    line_type: LineType::Scatter,
+                    cells: vec![(row, col)],
+                    count: 1,
+                    symbol: symbol.clone(),
+                    multiplier: 1.0,
+                    ways: 1,
+                }),
+            }
+        }
     }
+
+    wins.retain(|win| win.symbol.pay_for(win.count).is_some());
+    wins
 }
 
-#[derive(Debug, Clone)]
+// Find the best paying run from the leftmost reel: (paying symbol, count, wild multiplier)
+fn evaluate_line<'a>(symbols: &[&'a Symbol]) -> Option<(&'a Symbol, usize, f32)> {
+    let first = *symbols.first()?;
+    let mut best: Option<(&'a Symbol, usize, f32, f32)> = None; // (.., line value)
+
+    // Run of the first regular symbol, with wilds substituting where they're allowed to.
+    // Scatters never pay on lines
+    if let Some(&target) = symbols.iter().find(|s| !s.is_wild()).filter(|s| !s.is_scatter()) {
+        let count = symbols.iter().take_while(|s| s.matches(target)).count();
+        if let Some(pay) = target.pay_for(count) {
+            let multiplier: f32 = symbols[..count].iter()
+                .filter_map(|s| s.wild_rules())
+                .map(|rules| rules.multiplier)
+                .product();
+            best = Some((target, count, multiplier, pay * multiplier));
+        }
+    }
+
+    // Run of leading wilds paying as themselves
+    if first.wild_rules().is_some_and(|rules| rules.pays_as_itself) {
+        let count = symbols.iter().take_while(|s| s.name == first.name).count();
+        if let Some(pay) = first.pay_for(count)
+            && best.is_none_or(|(_, _, _, value)| pay > value)
+        {
+            best = Some((first, count, 1.0, pay));
+        }
+    }
+
+    best.map(|(symbol, count, multiplier, _)| (symbol, count, multiplier))
+}
+
+impl SlotMachine {
+    // Layouts that can be picked by name, e.g. `--machine 5x3`
+    pub const PRESETS: &'static [&'static str] = &["3x3", "3x3-bonus", "5x3", "5x4", "6x5", "megaways", "cluster"];
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "3x3" => Some(Self::default()),
+            "3x3-bonus" => Some(Self::classic_bonus()),
+            "5x3" => Some(Self::video_5x3()),
+            "5x4" => Some(Self::video_5x4()),
+            "6x5" => Some(Self::video_6x5()),
+            "megaways" => Some(Self::megaways_6()),
+            "cluster" => Some(Self::cluster_7x7()),
+            _ => None,
+        }
+    }
+
+    // 5x3 video slot on 20 paylines, about 95% RTP with the features. Stacked coins let the 6
+    // coins hold & spin needs land on 5 reels
+    pub fn video_5x3() -> Self {
+        Self::new(video_reels(5, 0.45, &[]))
+            .with_paylines(Payline::standard_5x3_20())
+            .with_free_spins(FreeSpinsConfig {
+                trigger_count: 3,
+                spins: 10,
+                multiplier: 2.0,
+                retrigger: true,
+                ..FreeSpinsConfig::default()
+            })
+            .with_hold_and_spin(HoldAndSpinConfig::default())
+            .with_hold_nudge(HoldNudgeConfig::default())
+    }
+
+    // 5x4 ways machine (1024 ways), no paylines to keep track of. Ways pay on any row, so the
+    // symbols are spread more evenly than on 5x3 or a common Cherry would pay nearly every spin.
+    // About 94% RTP with the features
+    pub fn video_5x4() -> Self {
+        let chances = [("Cherry", 16.0), ("Lemon", 15.0), ("Bell", 14.0), ("Star", 12.0), ("Seven", 10.0), ("Scatter", 1.5), ("Coin", 5.0), ("Wild", 0.5)];
+        Self::new(video_reels(5, 0.029, &chances))
+            .with_rows(4)
+            .with_evaluation(EvaluationMode::Ways)
+            .with_free_spins(FreeSpinsConfig {
+                trigger_count: 3,
+                spins: 10,
+                multiplier: 2.0,
+                retrigger: true,
+                ..FreeSpinsConfig::default()
+            })
+            .with_hold_and_spin(HoldAndSpinConfig {
+                trigger_count: 8,
+                ..HoldAndSpinConfig::default()
+            })
+    }
+
+    // 6x5 ways machine with cascades, the big grid keeps the tumbles going. About 94% RTP, free
+    // spins start about 1 in 130 spins, so a round rarely retriggers
+    pub fn video_6x5() -> Self {
+        let chances = [("Cherry", 16.0), ("Lemon", 15.0), ("Bell", 14.0), ("Star", 12.0), ("Seven", 10.0), ("Scatter", 2.0), ("Coin", 5.0), ("Wild", 0.5)];
+        Self::new(video_reels(6, 0.00107, &chances))
+            .with_rows(5)
+            .with_evaluation(EvaluationMode::Ways)
+            .with_cascades(CascadeConfig::default())
+            .with_free_spins(FreeSpinsConfig {
+                trigger_count: 4,
+                spins: 10,
+                multiplier: 2.0,
+                retrigger: true,
+                ..FreeSpinsConfig::default()
+            })
+    }
+
+    // Six reels showing 2 to 7 symbols each, with cascades. Spread out like the 6x5 symbols, about
+    // 94% RTP, and free spins start about 1 in 180 spins so a round rarely retriggers
+    pub fn megaways_6() -> Self {
+        let chances = [("Cherry", 16.0), ("Lemon", 15.0), ("Bell", 14.0), ("Star", 12.0), ("Seven", 10.0), ("Scatter", 2.0), ("Coin", 5.0), ("Wild", 0.5)];
+        Self::new(video_reels(6, 0.00185, &chances))
+            .with_megaways(MegawaysConfig::default())
+            .with_cascades(CascadeConfig::default())
+            .with_free_spins(FreeSpinsConfig {
+                trigger_count: 4,
+                spins: 10,
+                multiplier: 2.0,
+                retrigger: true,
+                ..FreeSpinsConfig::default()
+            })
+    }
+
+    // 7x7 grid paying groups of 5 or more, with cascades so a cleared cluster can make room for
+    // the next one. Every reel carries the same stops in its own order, a shared order would
+    // land whole rows of one symbol
+    pub fn cluster_7x7() -> Self {
+        // Cluster pays are keyed by group size, a size between two entries pays the smaller one
+        let sizes = [5, 7, 9, 12, 15];
+        let pays = |table: &[f32]| -> Vec<(usize, f32)> { sizes.into_iter().zip(table.iter().copied()).collect() };
+        let symbols = [
+            (Symbol::new("🍒", "Cherry", 0.0, 0.0, 6.0).with_pays(&pays(&[0.1, 0.2, 0.4, 1.2, 4.0])), 6),
+            (Symbol::new("🍋", "Lemon", 0.0, 0.0, 5.0).with_pays(&pays(&[0.12, 0.3, 0.6, 2.0, 6.0])), 5),
+            (Symbol::new("🔔", "Bell", 0.0, 0.0, 4.0).with_pays(&pays(&[0.25, 0.6, 1.2, 3.0, 10.0])), 4),
+            (Symbol::new("⭐", "Star", 0.0, 0.0, 3.0).with_pays(&pays(&[0.4, 1.0, 2.0, 6.0, 20.0])), 3),
+            // Two Sevens a strip can't group 15, even with cascades, so their table stops at 12
+            (Symbol::new("7️⃣", "Seven", 0.0, 0.0, 2.0).with_pays(&pays(&[0.8, 2.0, 4.0, 12.0])), 2),
+        ];
+        let stops: Vec<Symbol> = symbols.iter()
+            .flat_map(|(symbol, count)| std::iter::repeat_n(symbol.clone(), *count))
+            .collect();
+
+        // Strides coprime to the strip length shuffle it without losing or repeating a stop
+        let strides = [1, 3, 7, 9, 11, 13, 17];
+        let reels = strides.iter().enumerate()
+            .map(|(reel, stride)| Reel::from_strip((0..stops.len())
+                .map(|stop| ReelStop::new(stops[(stop * stride + reel * 3) % stops.len()].clone(), 1.0))
+                .collect()))
+            .collect();
+
+        Self::new(reels)
+            .with_rows(7)
+            .with_evaluation(EvaluationMode::Cluster { min_size: 5 })
+            .with_cascades(CascadeConfig::default())
+    }
+}
+
+// Symbol set for the 5 and 6 reel machines, paying for 3, 4 and 5+ of a kind. Every reel
+// shares it, the outer reels carry half the Sevens and Coins come in stacks of 3 so a window can
+// show several. `chances` sets how common each symbol is on this machine (symbols left out keep
+// the chance below) and `pay_scale` shrinks the pay table for grids with many more ways to win
+fn video_reels(reels: usize, pay_scale: f32, chances: &[(&str, f32)]) -> Vec<Reel> {
+    let mut symbols = vec![
+        Symbol::new("🍒", "Cherry", 0.5, 0.0, 20.0).with_pays(&[(3, 0.5), (4, 1.5), (5, 4.0)]),
+        Symbol::new("🍋", "Lemon", 0.8, 0.0, 18.0).with_pays(&[(3, 0.8), (4, 2.0), (5, 6.0)]),
+        Symbol::new("🔔", "Bell", 1.5, 0.0, 15.0).with_pays(&[(3, 1.5), (4, 4.0), (5, 12.0)]),
+        Symbol::new("⭐", "Star", 3.0, 0.0, 10.0).with_pays(&[(3, 3.0), (4, 10.0), (5, 30.0)]),
+        Symbol::new("7️⃣", "Seven", 5.0, 0.0, 6.0).with_pays(&[(3, 5.0), (4, 25.0), (5, 100.0)]),
+        Symbol::new("💎", "Scatter", 2.0, 0.0, 2.0)
+            .with_pays(&[(3, 2.0), (4, 10.0), (5, 50.0)])
+            .with_kind(SymbolKind::Scatter),
+        Symbol::new("🪙", "Coin", 0.0, 0.0, 4.0).with_pays(&[]).with_kind(SymbolKind::Coin),
+        Symbol::new("🃏", "Wild", 10.0, 0.0, 1.0)
+            .with_pays(&[(3, 10.0), (4, 50.0), (5, 200.0)])
+            .with_kind(SymbolKind::Wild(WildRules::default())),
+    ];
+    for symbol in &mut symbols {
+        if let Some(&(_, chance)) = chances.iter().find(|(name, _)| *name == symbol.name) {
+            symbol.chance = chance;
+        }
+        for pay in symbol.pays.values_mut() {
+            *pay *= pay_scale;
+        }
+    }
+
+    (0..reels)
+        .map(|reel| {
+            let outer = reel == 0 || reel == reels - 1;
+            let symbols = symbols.iter()
+                .map(|symbol| match symbol.name.as_str() {
+                    "Seven" if outer => Symbol { chance: symbol.chance / 2.0, ..symbol.clone() },
+                    _ => symbol.clone(),
+                })
+                .collect();
+            Reel::new(symbols).shuffled(reel as u64 + 1).stacked("Coin", 3)
+        })
+        .collect()
+}
+
+impl Default for SlotMachine {
+    // Classic 3 reel machine, only the symbols the game has textures for. The reels share one
+    // symbol set, but the last reel carries fewer Sevens so the top win lands less often than
+    // the first two reels suggest
+    fn default() -> Self {
+        let symbols = classic_symbols();
+        Self::new(vec![
+            Reel::new(symbols.clone()).shuffled(1),
+            Reel::new(symbols.clone()).shuffled(2),
+            Reel::new(symbols).shuffled(3).with_weight("Seven", 0.5),
+        ])
+        .with_paylines(Payline::standard_3x3())
+        .with_hold_nudge(HoldNudgeConfig::default())
+    }
+}
+
+impl SlotMachine {
+    // The classic machine with every feature symbol added: scatters for free spins, coins for
+    // hold & spin, mystery symbols and an expanding, sticky Wild (rarer on the last reel)
+    pub fn classic_bonus() -> Self {
+        let mut symbols = classic_symbols();
+        symbols.extend([
+            Symbol::new("💎", "Scatter", 5.0, 0.0, 3.0).with_kind(SymbolKind::Scatter),
+            Symbol::new("🪙", "Coin", 0.0, 0.0, 3.0).with_pays(&[]).with_kind(SymbolKind::Coin),
+            Symbol::new("❓", "Mystery", 0.0, 0.0, 2.0).with_pays(&[]).with_kind(SymbolKind::Mystery),
+            Symbol::new("🃏", "Wild", 25.0, 0.0, 1.0).with_kind(SymbolKind::Wild(WildRules {
+                expanding: true,
+                sticky_spins: 2,
+                ..WildRules::default()
+            })),
+        ]);
+
+        Self::new(vec![
+            Reel::new(symbols.clone()).shuffled(1),
+            Reel::new(symbols.clone()).shuffled(2),
+            Reel::new(symbols)
+                .shuffled(3)
+                .with_weight("Seven", 0.5)
+                .with_weight("Wild", 0.5),
+        ])
+        .with_paylines(Payline::standard_3x3())
+        .with_free_spins(FreeSpinsConfig {
+            trigger_count: 3,
+            spins: 10,
+            multiplier: 2.0,
+            retrigger: true,
+            ..FreeSpinsConfig::default()
+        })
+        .with_hold_and_spin(HoldAndSpinConfig {
+            trigger_count: 3,
+            ..HoldAndSpinConfig::default()
+        })
+        .with_hold_nudge(HoldNudgeConfig::default())
+    }
+}
+
+// The five symbols of the classic machine, paying for 3 of a kind
+fn classic_symbols() -> Vec<Symbol> {
+    vec![
+        Symbol::new("🍒", "Cherry", 2.0, 0.0, 50.0),
+        Symbol::new("🍋", "Lemon", 3.0, 0.0, 30.0),
+        Symbol::new("🔔", "Bell", 5.0, 0.0, 15.0),
+        Symbol::new("⭐", "Star", 10.0, 0.0, 4.0),
+        Symbol::new("7️⃣", "Seven", 20.0, 0.0, 1.0),
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum LineType {
-    Horizontal(usize),
-    Vertical(usize),
-    Diagonal(usize), // 0 = top-left→bottom-right, 1 = top-right→bottom-left
+    Payline(usize), // index into the machine's payline set
+    Ways,
+    Cluster,
+    Scatter,
 }
 
-#[derive(Debug)]
-pub struct WinningLine<'a> {
-    pub symbols: Vec<&'a Symbol>,
+#[derive(Debug, Clone)]
+pub struct WinningLine {
+    pub symbols: Vec<Symbol>,
     pub line_type: LineType,
-}
\ No newline at end of file
+    pub cells: Vec<(usize, usize)>, // (row, col) of every symbol in the win
+    pub count: usize, // how many reels matched from the left (cells in the group for clusters)
+    pub symbol: Symbol, // the symbol the line pays as (wilds take the symbol they substitute for)
+    pub multiplier: f32, // wild multipliers applied to the line win
+    pub ways: u32, // combinations paid (1 for paylines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::rng::GameRng;
+
+    fn names(grid: &[Vec<Symbol>]) -> Vec<String> {
+        grid.iter()
+            .map(|row| row.iter().map(|symbol| &symbol.name[..1]).collect())
+            .collect()
+    }
+
+    fn symbol(letter: char) -> Symbol {
+        match letter {
+            'W' => Symbol::new("🃏", "Wild", 5.0, 0.0, 1.0).with_kind(SymbolKind::Wild(WildRules::default())),
+            'S' => Symbol::new("💎", "Scatter", 2.0, 0.0, 1.0).with_kind(SymbolKind::Scatter),
+            'M' => Symbol::new("❓", "Mystery", 0.0, 0.0, 1.0).with_pays(&[]).with_kind(SymbolKind::Mystery),
+            'C' => Symbol::new("🍒", "Cherry", 1.0, 0.0, 1.0),
+            'L' => Symbol::new("🍋", "Lemon", 1.0, 0.0, 1.0),
+            _ => Symbol::new("🔔", "Bell", 1.0, 0.0, 1.0),
+        }
+    }
+
+    // A strip with exactly these stops, one letter each, equally weighted
+    fn strip(letters: &str) -> Reel {
+        Reel::from_strip(letters.chars().map(|letter| ReelStop::new(symbol(letter), 1.0)).collect())
+    }
+
+    // Row-major grid from one letter per cell, e.g. ["CWC", ...]
+    fn grid(rows: &[&str]) -> Vec<Vec<Symbol>> {
+        rows.iter()
+            .map(|row| row.chars().map(symbol).collect())
+            .collect()
+    }
+
+    fn machine(evaluation: EvaluationMode) -> SlotMachine {
+        SlotMachine::new(vec![strip("C"), strip("C"), strip("C")]).with_evaluation(evaluation)
+    }
+
+    #[test]
+    fn the_same_seed_lands_the_same_spins() {
+        let machine = SlotMachine::classic_bonus();
+        let (mut first, mut second) = (GameRng::from_seed(42), GameRng::from_seed(42));
+        for _ in 0..20 {
+            let a = machine.spin_outcome_with_rng(first.rng());
+            let b = machine.spin_outcome_with_rng(second.rng());
+            assert_eq!(a.stops, b.stops);
+            assert_eq!(names(a.final_grid()), names(b.final_grid()));
+        }
+    }
+
+    #[test]
+    fn strips_carry_symbols_in_proportion_to_their_chance() {
+        let chance = |letter: char, chance: f32| Symbol { chance, ..symbol(letter) };
+        let reel = Reel::new(vec![chance('C', 30.0), chance('L', 20.0), chance('B', 10.0), chance('S', 0.0)]);
+        let stops = |name: &str| reel.strip().iter().filter(|stop| stop.symbol.name == name).count();
+
+        assert_eq!((stops("Cherry"), stops("Lemon"), stops("Bell"), stops("Scatter")), (3, 2, 1, 1));
+        assert!(reel.strip().iter().all(|stop| stop.weight == if stop.symbol.is_scatter() { 0.0 } else { 1.0 }));
+        // Copies are spread out rather than stacked
+        assert!(reel.strip().windows(2).all(|pair| pair[0].symbol.name != pair[1].symbol.name));
+
+        let long = Reel::new(vec![chance('C', 1000.0), chance('L', 1.0)]);
+        assert_eq!(long.len(), MAX_STRIP_STOPS + 1);
+    }
+
+    #[test]
+    fn stacks_gather_a_symbol_where_its_runs_start() {
+        let reel = strip("CLCLCL").stacked("Cherry", 2);
+        let letters: String = reel.strip().iter().map(|stop| &stop.symbol.name[..1]).collect();
+        assert_eq!(letters, "CCLLCL");
+    }
+
+    #[test]
+    fn window_shows_consecutive_stops_around_the_strip() {
+        let reel = strip("CLBW");
+        let window = reel.window(2, 3);
+        let shown: Vec<&str> = window.iter().map(|symbol| symbol.name.as_str()).collect();
+        assert_eq!(shown, ["Bell", "Wild", "Cherry"]);
+    }
+
+    #[test]
+    fn paylines_read_their_own_cells() {
+        let wins = machine(EvaluationMode::Lines).check_wins(&grid(&["BBB", "CCC", "LCL"]));
+
+        let lines: Vec<&LineType> = wins.iter().map(|win| &win.line_type).collect();
+        assert_eq!(lines, [&LineType::Payline(0), &LineType::Payline(1)]);
+        assert_eq!(wins[0].cells, vec![(1, 0), (1, 1), (1, 2)]);
+        assert_eq!(wins[1].cells, vec![(0, 0), (0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn lines_pay_the_run_from_the_leftmost_reel() {
+        let machine = SlotMachine::new(vec![strip("C"); 5]).with_paylines(vec![Payline::new(&[0, 0, 0, 0, 0])]);
+        let pays = |row: &str| -> Vec<Vec<Symbol>> {
+            vec![row.chars().map(|letter| symbol(letter).with_pays(&[(3, 1.0), (4, 3.0)])).collect()]
+        };
+
+        let wins = machine.check_wins(&pays("CCCCL"));
+        assert_eq!(wins.len(), 1);
+        assert_eq!(wins[0].count, 4);
+        assert_eq!(wins[0].symbol.pay_for(wins[0].count), Some(3.0));
+        assert_eq!(wins[0].symbol.pay_for(5), Some(3.0));
+        assert!(machine.check_wins(&pays("LCCCC")).is_empty());
+    }
+
+    #[test]
+    fn wilds_substitute_on_lines() {
+        let wins = machine(EvaluationMode::Lines).check_wins(&grid(&["LBL", "CWC", "BLB"]));
+
+        assert_eq!(wins.len(), 1);
+        assert_eq!(wins[0].line_type, LineType::Payline(0));
+        assert_eq!(wins[0].symbol.name, "Cherry");
+        assert_eq!(wins[0].count, 3);
+        assert_eq!(wins[0].cells, vec![(1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn scatters_pay_anywhere_but_never_on_lines() {
+        let wins = machine(EvaluationMode::Lines).check_wins(&grid(&["SLB", "BSL", "LBS"]));
+
+        assert_eq!(wins.len(), 1);
+        assert_eq!(wins[0].line_type, LineType::Scatter);
+        assert_eq!(wins[0].count, 3);
+    }
+
+    #[test]
+    fn ways_multiply_the_matching_cells_on_each_reel() {
+        let wins = machine(EvaluationMode::Ways).check_wins(&grid(&["CCL", "CLC"]));
+
+        assert_eq!(wins.len(), 1);
+        assert_eq!(wins[0].symbol.name, "Cherry");
+        assert_eq!(wins[0].count, 3);
+        assert_eq!(wins[0].ways, 2);
+        assert_eq!(wins[0].cells.len(), 4);
+    }
+
+    #[test]
+    fn cluster_joins_orthogonal_neighbours_through_wilds() {
+        let mut cells = grid(&["CCL", "LWB", "BCC"]);
+        for symbol in cells.iter_mut().flatten().filter(|symbol| symbol.name == "Cherry") {
+            *symbol = symbol.clone().with_pays(&[(5, 1.0)]);
+        }
+        let wins = machine(EvaluationMode::Cluster { min_size: 5 }).check_wins(&cells);
+
+        assert_eq!(wins.len(), 1);
+        assert_eq!(wins[0].line_type, LineType::Cluster);
+        assert_eq!(wins[0].count, 5);
+        assert!(wins[0].cells.contains(&(1, 1)));
+    }
+
+    #[test]
+    fn cascade_refills_from_the_stops_above_the_window() {
+        let machine = SlotMachine::new(vec![strip("BLCB"), strip("LBCL"), strip("BLCB")])
+            .with_paylines(vec![Payline::new(&[1, 1, 1])])
+            .with_cascades(CascadeConfig::default());
+        let outcome = machine.outcome_at(vec![1, 1, 1], vec![3, 3, 3]);
+
+        assert_eq!(names(outcome.initial_grid()), ["LBL", "CCC", "BLB"]);
+        assert_eq!(outcome.steps.len(), 2);
+        assert_eq!(outcome.steps[1].removed, vec![(1, 0), (1, 1), (1, 2)]);
+        assert_eq!(outcome.steps[1].multiplier, 2.0);
+        assert_eq!(names(outcome.final_grid()), ["BLB", "LBL", "BLB"]);
+        assert!(outcome.steps[1].wins.is_empty());
+    }
+
+    #[test]
+    fn cascade_reveals_mystery_symbols_dropping_in() {
+        let machine = SlotMachine::new(vec![strip("MLCB"), strip("LBCL"), strip("BLCB")])
+            .with_paylines(vec![Payline::new(&[1, 1, 1])])
+            .with_cascades(CascadeConfig::default());
+        let outcome = machine.outcome_with_modifiers(vec![1, 1, 1], vec![3, 3, 3], &[], Some(&symbol('B')));
+
+        assert_eq!(names(outcome.initial_grid()), ["LBL", "CCC", "BLB"]);
+        assert!(outcome.modifiers.mystery.is_empty());
+        assert_eq!(names(outcome.final_grid()), ["BLB", "LBL", "BLB"]);
+    }
+
+    #[test]
+    fn previous_wraps_around_the_strip() {
+        let reel = strip("CLB");
+        assert_eq!(reel.previous(0), 2);
+        assert_eq!(reel.previous(2), 1);
+    }
+
+    #[test]
+    fn empty_reels_and_zero_weights_do_not_panic() {
+        let mut rng = GameRng::from_seed(1);
+        let empty = Reel::new(Vec::new());
+        assert_eq!(empty.spin_stop_with_rng(rng.rng()), 0);
+        assert!(empty.symbol_at(3).is_padding());
+        assert_eq!(empty.previous(0), 0);
+        assert_eq!(empty.nudge(0), 0);
+
+        let unweighted = strip("CL").with_weight("Cherry", 0.0).with_weight("Lemon", 0.0);
+        assert!(unweighted.spin_stop_with_rng(rng.rng()) < 2);
+
+        let machine = SlotMachine::new(vec![empty, strip("C"), strip("C")]).with_cascades(CascadeConfig::default());
+        let outcome = machine.outcome_at(vec![0, 0, 0], vec![3, 3, 3]);
+        assert!(!outcome.has_wins());
+    }
+
+    #[test]
+    fn rows_pad_short_reels_and_read_back_without_padding() {
+        let columns = vec![
+            vec![symbol('C'), symbol('L')],
+            vec![symbol('B'), symbol('C'), symbol('L')],
+        ];
+        let rows = Grid::from_columns(columns).to_rows();
+
+        assert_eq!(rows.len(), 3);
+        assert!(rows[2][0].is_padding());
+        assert!(!rows[2][0].matches(&rows[2][0]));
+        assert_eq!(Grid::column_height(&rows, 0), 2);
+        assert_eq!(Grid::column_height(&rows, 1), 3);
+        assert_eq!(Grid::from_rows(&rows).columns()[0].len(), 2);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod core;
+pub mod entities;
+#[cfg(feature = "ui")]
+pub mod ui;
+pub mod util;
\ No newline at end of file
@@ -1,15 +1,77 @@
-mod util;
-mod entities;
-mod core;
-mod ui;
-
 use bevy::prelude::*;
-use entities::slot_machine::{Symbol, Reel, SlotMachine};
-use ui::slot_ui::GameState;
-use ui::game_ui::GameUIPlugin;
-use ui::assets::AssetsPlugin;
+use slot_machine::core;
+use slot_machine::entities::slot_machine::SlotMachine;
+use slot_machine::core::config::{LaunchCommand, LaunchOptions};
+use slot_machine::core::rng::GameRng;
+use slot_machine::ui;
+use slot_machine::ui::slot_ui::GameState;
+use slot_machine::ui::game_ui::GameUIPlugin;
+use slot_machine::ui::assets::AssetsPlugin;
+use slot_machine::util::textures::SYMBOL_TEXTURES;
+use slot_machine::util;
 
 fn main() {
+    let options = match LaunchOptions::from_args() {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("usage: slot-machine [rtp | validate] [--seed N | --daily] [--machine NAME|FILE]");
+            std::process::exit(2);
+        }
+    };
+
+    let slot_machine = match options.machine.as_deref().map(util::json::load_machine) {
+        Some(Ok(machine)) => machine,
+        Some(Err(err)) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+        None => SlotMachine::default(),
+    };
+    println!("🎰 Machine: {} reels x {} rows", slot_machine.cols(), slot_machine.rows());
+
+    if options.command == LaunchCommand::Validate {
+        let problems = core::validation::validate_machine(&slot_machine, &SYMBOL_TEXTURES.map(|(name, _)| name));
+        let errors = problems.iter().filter(|problem| !problem.is_warning()).count();
+        for problem in &problems {
+            if problem.is_warning() {
+                println!("⚠️ {}", problem);
+            } else {
+                println!("❌ {}", problem);
+            }
+        }
+        println!("{} errors, {} warnings", errors, problems.len() - errors);
+        std::process::exit(if errors > 0 { 1 } else { 0 });
+    }
+
+    // Check the machine before anything spins it, a bad weight would panic mid-spin
+    let warnings = core::validation::validate_or_exit(&slot_machine);
+    if !warnings.is_empty() {
+        println!("⚠️ {} warnings about this machine, run with `validate` to see them", warnings.len());
+    }
+
+    if options.command == LaunchCommand::Rtp {
+        match core::math::base_game_rtp(&slot_machine) {
+            Ok(report) => println!("{}", report),
+            Err(err) => {
+                eprintln!("{}", err);
+                eprintln!("   cargo run --release --no-default-features --bin slot-sim -- --machine {}",
+                    options.machine.as_deref().unwrap_or("3x3"));
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let game_rng = match options.seed {
+        Some(seed) => GameRng::from_seed(seed),
+        None => GameRng::from_entropy(),
+    };
+    println!("🎲 Session seed: {} (replay with --seed {})", game_rng.seed(), game_rng.seed());
+
+    // Jackpot pools carry over between sessions, every machine keeps its own
+    let jackpot = util::json::get_jackpot(options.machine.as_deref().unwrap_or("3x3"), &slot_machine);
+
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
@@ -21,33 +83,25 @@ fn main() {
             }),
             ..default()
         }))
+        .insert_resource(game_rng)
+        .insert_resource(jackpot)
+        // The slot machine is the single source of spin outcomes and payouts, the UI is built from its layout
+        .insert_resource(slot_machine)
         .add_plugins(AssetsPlugin) // Load assets first
         .add_plugins(GameUIPlugin)
-        .add_systems(Update, ui::slot_animation::update_slot_animation)
+        .add_systems(Update, (
+            ui::slot_animation::update_slot_animation,
+            ui::slot_animation::update_cascade_animation,
+            ui::slot_animation::update_cell_spin_animation,
+            ui::slot_animation::update_mystery_reveal,
+            ui::slot_animation::settle_spin_outcome,
+        ))
         .add_systems(Startup, setup_game)
         .run();
 }
 
 fn setup_game(mut commands: Commands) {
-    let symbols = vec![
-        Symbol::new("🍒", "Cherry", 2.0, 0.0, 50.0),
-        Symbol::new("🍋", "Lemon", 3.0, 0.0, 30.0),
-        Symbol::new("🔔", "Bell", 5.0, 0.0, 15.0),
-        Symbol::new("⭐", "Star", 10.0, 0.0, 4.0),
-        Symbol::new("7️⃣", "Seven", 20.0, 0.0, 1.0),
-    ];
-
-    let slot_machine = SlotMachine::new(vec![
-        Reel::new(symbols.clone()),
-        Reel::new(symbols.clone()),
-        Reel::new(symbols.clone()),
-    ]);
-
-    // Insert slot machine as separate resource for animation system
-    commands.insert_resource(slot_machine.clone());
-
     let game_state = GameState {
-        slot_machine,
         player_pool: 100.0,
         current_bet: 5.0,
         last_grid: None,
@@ -55,6 +109,7 @@ fn setup_game(mut commands: Commands) {
         is_spinning: false,
         last_win_amount: 0.0,
         has_recent_win: false,
+        sticky_wilds: Vec::new(),
     };
 
     commands.insert_resource(game_state);
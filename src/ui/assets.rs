@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use std::collections::HashMap;
+use crate::util::textures::SYMBOL_TEXTURES;
 
 /// Resource containing all loaded symbol textures
 #[derive(Resource)]
@@ -53,20 +54,13 @@ fn load_assets(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
 ) {
-    // Load symbol textures
-    let cherry = asset_server.load("textures/symbols/cherry.png");
-    let lemon = asset_server.load("textures/symbols/lemon.png");
-    let bell = asset_server.load("textures/symbols/bell.png");
-    let star = asset_server.load("textures/symbols/star.png");
-    let seven = asset_server.load("textures/symbols/seven.png");
-
-    // Create symbol map for dynamic lookup
-    let mut symbol_map = HashMap::new();
-    symbol_map.insert("Cherry".to_string(), cherry.clone());
-    symbol_map.insert("Lemon".to_string(), lemon.clone());
-    symbol_map.insert("Bell".to_string(), bell.clone());
-    symbol_map.insert("Star".to_string(), star.clone());
-    symbol_map.insert("Seven".to_string(), seven.clone());
+    // Load symbol textures, mapped by symbol name for dynamic lookup
+    let symbol_map: HashMap<String, Handle<Image>> = SYMBOL_TEXTURES.iter()
+        .map(|&(name, path)| (name.to_string(), asset_server.load(path)))
+        .collect();
+    let texture = |name: &str| symbol_map[name].clone();
+    let (cherry, lemon, bell, star, seven) =
+        (texture("Cherry"), texture("Lemon"), texture("Bell"), texture("Star"), texture("Seven"));
 
     let symbol_assets = SymbolAssets {
         cherry,
@@ -94,32 +94,32 @@ pub fn handle_bet_input(
         for event in char_input_events.read() {
             input_field.is_editing = true;
             let character_str = event.char.as_str();
-            if let Some(character) = character_str.chars().next() {
-                if character.is_ascii_digit() || character == '.' {
-                    let current_text = &text.sections[0].value;
-                    let number_part = &current_text[1..]; // Remove $ prefix
-                    
-                    // Prevent multiple decimal points
-                    if character == '.' && number_part.contains('.') {
-                        continue;
-                    }
-                    
-                    let new_text = format!("{}{}", current_text, character);
-                    
-                    // Update game state in real-time as user types
-                    let new_number_part = &new_text[1..];
-                    if !new_number_part.is_empty() {
-                        if let Ok(bet_amount) = new_number_part.parse::<f32>() {
-                            let clamped_bet = bet_amount.clamp(1.0, game_state.player_pool);
-                            if clamped_bet != game_state.current_bet {
-                                println!("🎯 Bet updated from typing: ${:.2} -> ${:.2}", game_state.current_bet, clamped_bet);
-                                game_state.current_bet = clamped_bet;
-                            }
-                        }
+            if let Some(character) = character_str.chars().next()
+                && (character.is_ascii_digit() || character == '.')
+            {
+                let current_text = &text.sections[0].value;
+                let number_part = &current_text[1..]; // Remove $ prefix
+                
+                // Prevent multiple decimal points
+                if character == '.' && number_part.contains('.') {
+                    continue;
+                }
+                
+                let new_text = format!("{}{}", current_text, character);
+                
+                // Update game state in real-time as user types
+                let new_number_part = &new_text[1..];
+                if !new_number_part.is_empty()
+                    && let Ok(bet_amount) = new_number_part.parse::<f32>()
+                {
+                    let clamped_bet = bet_amount.clamp(1.0, game_state.player_pool);
+                    if clamped_bet != game_state.current_bet {
+                        println!("🎯 Bet updated from typing: ${:.2} -> ${:.2}", game_state.current_bet, clamped_bet);
+                        game_state.current_bet = clamped_bet;
                     }
-                    
-                    text.sections[0].value = new_text;
                 }
+                
+                text.sections[0].value = new_text;
             }
         }
 
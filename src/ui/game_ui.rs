@@ -6,10 +6,17 @@ pub struct GameUIPlugin;
 impl Plugin for GameUIPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(SlotAnimationState::default())
+           .init_resource::<crate::ui::slot_ui::FreeSpinsState>()
+           .init_resource::<crate::ui::slot_ui::HoldAndSpinRound>()
+           .init_resource::<crate::ui::slot_ui::HoldNudgeState>()
+           .init_resource::<crate::ui::slot_ui::GambleState>()
            .add_systems(Startup, crate::ui::slot_ui::setup_ui)
                        .add_systems(Update, (
                 crate::ui::slot_ui::handle_spin_button,
-                crate::ui::slot_ui::update_slot_display_animation, // Handles both spinning and stopped reels
+                (
+                    crate::ui::slot_ui::update_slot_display_animation, // Handles both spinning and stopped reels
+                    crate::ui::slot_ui::resize_reel_cells,
+                ),
                 crate::ui::slot_ui::update_displays,
                 crate::ui::slot_ui::update_spin_button_text,
                 crate::ui::slot_ui::process_spin_results,
@@ -18,6 +25,16 @@ impl Plugin for GameUIPlugin {
                 crate::ui::slot_ui::show_win_popup,
                 crate::ui::slot_ui::animate_coin_flood,
                 crate::ui::slot_ui::update_win_popup,
+                crate::ui::slot_ui::update_free_spins_display,
+                crate::ui::slot_ui::show_free_spins_summary,
+                crate::ui::slot_ui::update_free_spins_summary,
+                crate::ui::slot_ui::update_jackpot_display,
+                crate::ui::slot_ui::handle_hold_nudge_buttons,
+                crate::ui::slot_ui::update_hold_nudge_buttons,
+                (
+                    crate::ui::slot_ui::show_gamble_panel,
+                    crate::ui::slot_ui::handle_gamble_buttons,
+                ),
                 crate::ui::bet_controls::handle_bet_controls,
                 crate::ui::bet_controls::handle_bet_input,
             ));
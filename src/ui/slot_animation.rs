@@ -1,14 +1,45 @@
 use bevy::prelude::*;
-use crate::entities::slot_machine::{Symbol, SlotMachine};
-use crate::ui::slot_ui::{GameState, SlotCell, SimpleWinningLine};
-use rand::Rng;
+use crate::entities::slot_machine::{Symbol, SlotMachine, SpinOutcome, Grid, StickyWild};
+use crate::ui::slot_ui::{GameState, HoldAndSpinRound, GambleState, WinPopupState, BonusFeatures, open_pending_win_popup};
+use crate::core::scoring::outcome_payout;
+use crate::core::rng::GameRng;
+use crate::util::json::save_jackpot;
+
+/// Cascade animation phases, after the reels have stopped
+#[derive(Clone, PartialEq, Debug)]
+pub enum CascadePhase {
+    Idle,
+    Blooming, // the current step's wins are being highlighted
+    Falling, // symbols are dropping into the next step's grid
+}
+
+/// A single cell spinning on its own during a hold-and-spin respin
+#[derive(Clone, Debug)]
+pub struct CellSpin {
+    pub row: usize,
+    pub col: usize,
+    pub timer: Timer, // the cell lands on its result when this finishes
+}
 
 #[derive(Resource)]
 pub struct SlotAnimationState {
     pub is_animating: bool,
+    pub outcome: Option<SpinOutcome>,
+    pub spin_sticky: Vec<StickyWild>, // sticky wilds the spin started with, a nudge re-evaluates from them
+    pub spin_mystery: Option<Symbol>, // what mystery symbols reveal as this spin, nudged in or not
+    pub cascade_phase: CascadePhase,
+    pub cascade_index: usize,
+    pub fall_timer: Timer,
+    pub fall_rows: Vec<Vec<usize>>, // [row][col] how far each cell drops in the current cascade
+    pub display_grid: Option<Vec<Vec<Symbol>>>, // shown instead of the reel strips during cascades and respins
+    pub cell_spins: Vec<CellSpin>, // unlocked cells still spinning in a hold-and-spin respin
+    pub revealing: bool, // mystery symbols are flipping over to the symbol they became
+    pub reveal_timer: Timer,
+    pub settle_pending: bool, // the reels have stopped but the spin hasn't been paid yet
     pub current_spinning_reel: Option<usize>,
     pub columns: Vec<SlotColumn>,
     pub completed_reels: Vec<bool>,
+    pub spinning_reels: Vec<bool>, // reels moving this spin, held reels stay put
     pub results: Vec<Vec<Symbol>>,
     pub final_symbols: Vec<Vec<Symbol>>,
     pub target_results: Vec<Vec<Symbol>>,
@@ -20,18 +51,132 @@ impl Default for SlotAnimationState {
     fn default() -> Self {
         Self {
             is_animating: false,
+            outcome: None,
+            spin_sticky: Vec::new(),
+            spin_mystery: None,
+            cascade_phase: CascadePhase::Idle,
+            cascade_index: 0,
+            fall_timer: Timer::from_seconds(0.4, TimerMode::Once),
+            fall_rows: Vec::new(),
+            display_grid: None,
+            cell_spins: Vec::new(),
+            revealing: false,
+            reveal_timer: Timer::from_seconds(0.8, TimerMode::Once),
+            settle_pending: false,
             current_spinning_reel: None,
             columns: Vec::new(),
-            completed_reels: vec![false; 3],
-            results: vec![Vec::new(); 3],
-            final_symbols: vec![Vec::new(); 3],
-            target_results: vec![Vec::new(); 3],
+            completed_reels: Vec::new(),
+            spinning_reels: Vec::new(),
+            results: Vec::new(),
+            final_symbols: Vec::new(),
+            target_results: Vec::new(),
             animation_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
             deceleration_factor: 0.98,
         }
     }
 }
 
+impl SlotAnimationState {
+    // Reels spinning, mystery symbols revealing, cascades still playing out or cells respinning
+    pub fn is_busy(&self) -> bool {
+        self.is_animating || self.settle_pending || self.cascade_phase != CascadePhase::Idle || self.is_respinning()
+    }
+
+    pub fn start_mystery_reveal(&mut self) {
+        self.revealing = true;
+        self.reveal_timer = Timer::from_seconds(0.8, TimerMode::Once);
+        if let Some(symbol) = self.outcome.as_ref().and_then(|outcome| outcome.modifiers.revealed.as_ref()) {
+            println!("❓ Mystery symbols reveal as {}!", symbol.name);
+        }
+    }
+
+    pub fn is_revealing(&self) -> bool {
+        self.revealing
+    }
+
+    // A mystery cell still showing its mystery face (the first half of the flip)
+    pub fn is_mystery_face_down(&self, row: usize, col: usize) -> bool {
+        self.revealing && self.reveal_timer.fraction() < 0.5 && self.is_mystery_cell(row, col)
+    }
+
+    pub fn is_mystery_cell(&self, row: usize, col: usize) -> bool {
+        self.outcome.as_ref().is_some_and(|outcome| outcome.modifiers.mystery.contains(&(row, col)))
+    }
+
+    // Horizontal scale of a flipping mystery cell, it narrows to nothing and opens up again
+    pub fn reveal_scale(&self) -> f32 {
+        if !self.revealing {
+            return 1.0;
+        }
+        (self.reveal_timer.fraction() * std::f32::consts::PI).cos().abs().max(0.05)
+    }
+
+    pub fn is_respinning(&self) -> bool {
+        !self.cell_spins.is_empty()
+    }
+
+    // The symbol a respinning cell shows right now, cycling through its reel strip
+    pub fn cell_spin_symbol(&self, row: usize, col: usize) -> Option<&Symbol> {
+        let spin = self.cell_spins.iter().find(|spin| spin.row == row && spin.col == col)?;
+        let column = self.columns.get(col)?;
+        if column.reel_symbols.is_empty() {
+            return None;
+        }
+        let index = (spin.timer.elapsed_secs() * 15.0) as usize + row * 7;
+        Some(&column.reel_symbols[index % column.reel_symbols.len()])
+    }
+
+    // The sticky wild held on a cell for the spin being shown
+    pub fn sticky_symbol(&self, row: usize, col: usize) -> Option<&Symbol> {
+        let outcome = self.outcome.as_ref()?;
+        outcome.modifiers.sticky.iter()
+            .find(|wild| wild.row == row && wild.col == col)
+            .map(|wild| &wild.symbol)
+    }
+
+    // Whether an expanding wild filled this cell, once the reels have stopped
+    pub fn is_expanded(&self, row: usize, col: usize) -> bool {
+        !self.is_animating && self.cascade_index == 0 && self.outcome.as_ref()
+            .is_some_and(|outcome| outcome.modifiers.expanded.contains(&(row, col)))
+    }
+
+    pub fn has_next_cascade(&self) -> bool {
+        self.outcome.as_ref().is_some_and(|outcome| self.cascade_index + 1 < outcome.steps.len())
+    }
+
+    // Move on to the next cascade step and start dropping its symbols in
+    pub fn start_cascade_fall(&mut self) {
+        let Some(outcome) = &self.outcome else { return };
+        let Some(step) = outcome.steps.get(self.cascade_index + 1) else { return };
+
+        let rows = step.grid.len();
+        let cols = step.grid.first().map_or(0, |row| row.len());
+        self.fall_rows = cascade_fall_rows(&step.removed, rows, cols);
+        self.display_grid = Some(step.grid.clone());
+        self.cascade_index += 1;
+        self.cascade_phase = CascadePhase::Falling;
+        self.fall_timer = Timer::from_seconds(0.4, TimerMode::Once);
+        println!("⬇️ Cascade {}! Multiplier x{}", self.cascade_index, step.multiplier);
+    }
+}
+
+// How many rows each cell of the new grid has fallen, given the cells removed from the old grid
+fn cascade_fall_rows(removed: &[(usize, usize)], rows: usize, cols: usize) -> Vec<Vec<usize>> {
+    let mut fall = vec![vec![0; cols]; rows];
+    for col in 0..cols {
+        let kept: Vec<usize> = (0..rows).filter(|&row| !removed.contains(&(row, col))).collect();
+        let missing = rows - kept.len();
+        // New symbols come in from above the window
+        for row_fall in fall.iter_mut().take(missing) {
+            row_fall[col] = missing;
+        }
+        for (i, old_row) in kept.into_iter().enumerate() {
+            fall[missing + i][col] = missing + i - old_row;
+        }
+    }
+    fall
+}
+
 #[derive(Component, Clone)]
 pub struct SlotColumn {
     pub reel_symbols: Vec<Symbol>,
@@ -41,10 +186,11 @@ pub struct SlotColumn {
     pub is_spinning: bool,
     pub symbol_height: f32,
     pub column: usize,
+    pub visible_rows: usize,
 }
 
 impl SlotColumn {
-    pub fn new(symbols: Vec<Symbol>, column_index: usize) -> Self {
+    pub fn new(symbols: Vec<Symbol>, column_index: usize, visible_rows: usize) -> Self {
         Self {
             reel_symbols: symbols,
             current_offset: 0.0,
@@ -53,6 +199,7 @@ impl SlotColumn {
             is_spinning: false,
             symbol_height: 100.0,
             column: column_index,
+            visible_rows,
         }
     }
 }
@@ -85,9 +232,11 @@ pub fn generate_circular_reel(base_symbols: &[Symbol], reel_length: usize) -> Ve
 
 pub fn start_slot_animation(
     mut animation_state: ResMut<SlotAnimationState>,
-    mut game_state: ResMut<GameState>,
+    game_state: ResMut<GameState>,
     slot_machine: Res<SlotMachine>,
-    mut commands: Commands,
+    mut game_rng: ResMut<GameRng>,
+    commands: Commands,
+    held: Vec<bool>,
 ) {
     if animation_state.is_animating {
         return;
@@ -95,47 +244,76 @@ pub fn start_slot_animation(
 
     println!("Starting slot animation...");
     
-    // Generate final results directly as columns (3 symbols per column)
-    let base_symbols = vec![
-        Symbol::new("🍒", "Cherry", 2.0, 0.0, 50.0),
-        Symbol::new("🍋", "Lemon", 3.0, 0.0, 30.0),
-        Symbol::new("🔔", "Bell", 5.0, 0.0, 15.0),
-        Symbol::new("⭐", "Star", 10.0, 0.0, 4.0),
-        Symbol::new("7️⃣", "Seven", 50.0, 0.0, 1.0),
-    ];
-    
-    // Generate random results ensuring no duplicates in any column (reel)
-    let mut target_columns = Vec::new();
-    let mut rng = rand::thread_rng();
-    
-    // Generate column by column to ensure no duplicates in vertical lines (reels)
-    for _col_idx in 0..3 {
-        let mut available_symbols = base_symbols.clone();
-        let mut column_symbols = Vec::new();
-        
-        for _row in 0..3 {
-            // Randomly select from remaining available symbols for this column
-            let random_index = rng.gen_range(0..available_symbols.len());
-            let selected_symbol = available_symbols.remove(random_index);
-            column_symbols.push(selected_symbol);
+    // The slot machine decides the outcome (respecting reel weights), the animation only shows it.
+    // Held reels keep the stop they landed on last spin
+    let stops = match &animation_state.outcome {
+        Some(previous) if held.contains(&true) => {
+            slot_machine.spin_stops_held_with_rng(game_rng.rng(), &previous.stops, &held)
+        }
+        _ => slot_machine.spin_stops_with_rng(game_rng.rng()),
+    };
+    let spinning: Vec<bool> = (0..stops.len()).map(|i| !held.get(i).copied().unwrap_or(false)).collect();
+    // Megaways reels pick a new height as they spin, a held reel keeps the one it had
+    let mut heights = slot_machine.spin_heights_with_rng(game_rng.rng());
+    if let Some(previous) = &animation_state.outcome {
+        for (height, (&spins, &previous_height)) in heights.iter_mut().zip(spinning.iter().zip(&previous.heights)) {
+            if !spins {
+                *height = previous_height;
+            }
         }
-        
-        target_columns.push(column_symbols);
     }
+    let mystery = slot_machine.roll_mystery(game_rng.rng());
+    let outcome = slot_machine.outcome_with_modifiers(stops, heights, &game_state.sticky_wilds, mystery.as_ref());
+    animation_state.spin_sticky = game_state.sticky_wilds.clone();
+    animation_state.spin_mystery = mystery;
+    play_reels(animation_state, game_state, slot_machine, commands, outcome, spinning, 3);
+}
 
-    println!("Generated final column results:");
-    for (col_index, column) in target_columns.iter().enumerate() {
-        println!("Column {}: {:?}", col_index, column.iter().map(|s| &s.name).collect::<Vec<_>>());
-        println!("  Layout - Top: {}, Middle: {}, Bottom: {}", 
-            column[0].name, column[1].name, column[2].name);
+// Nudge one reel a single position, only that reel moves and the grid is evaluated again
+pub fn start_nudge_animation(
+    animation_state: ResMut<SlotAnimationState>,
+    game_state: ResMut<GameState>,
+    slot_machine: Res<SlotMachine>,
+    commands: Commands,
+    reel: usize,
+) {
+    if animation_state.is_animating {
+        return;
     }
+    let Some(previous) = &animation_state.outcome else { return };
+
+    println!("👉 Nudging reel {}...", reel);
+    let stops = slot_machine.nudge_stops(&previous.stops, reel);
+    let spinning = (0..stops.len()).map(|i| i == reel).collect();
+    // The nudged grid is evaluated from where the spin started: the same sticky wilds (so they only
+    // count down once) and the same reveal for any mystery symbols the nudge brings into view
+    let outcome = slot_machine.outcome_with_modifiers(stops, previous.heights.clone(), &animation_state.spin_sticky, animation_state.spin_mystery.as_ref());
+    play_reels(animation_state, game_state, slot_machine, commands, outcome, spinning, 1);
+}
+
+// Spin the given reels onto the outcome's stops, one after another. Reels that aren't spinning stay
+// where they are
+fn play_reels(
+    mut animation_state: ResMut<SlotAnimationState>,
+    mut game_state: ResMut<GameState>,
+    slot_machine: Res<SlotMachine>,
+    mut commands: Commands,
+    outcome: SpinOutcome,
+    spinning: Vec<bool>,
+    min_travel: usize,
+) {
+    let stops = outcome.stops.clone();
+    let final_grid = outcome.initial_grid().to_vec();
     
-    // Convert to row format for win checking (this matches what the console will show)
-    let mut final_grid = vec![Vec::new(); 3];
-    for row_idx in 0..3 {
-        for col_idx in 0..3 {
-            final_grid[row_idx].push(target_columns[col_idx][row_idx].clone());
-        }
+    // Convert to column format for the reels (one symbol per visible row, reels can differ in height)
+    let target_columns = Grid::from_rows(&final_grid).columns().to_vec();
+    if slot_machine.megaways().is_some() {
+        println!("🎰 Reel heights {:?}: {} ways", outcome.heights, outcome.ways());
+    }
+
+    println!("Generated final column results (top to bottom):");
+    for (col_index, column) in target_columns.iter().enumerate() {
+        println!("Column {}: {:?}", col_index, column.iter().map(|s| &s.name).collect::<Vec<_>>());
     }
     
     println!("Final grid in row format (for win checking):");
@@ -151,43 +329,65 @@ pub fn start_slot_animation(
     
     // Initialize animation state
     animation_state.is_animating = true;
-    animation_state.current_spinning_reel = Some(0); // Start with first reel
+    animation_state.outcome = Some(outcome);
+    animation_state.cascade_phase = CascadePhase::Idle;
+    animation_state.cascade_index = 0;
+    animation_state.fall_rows.clear();
+    animation_state.display_grid = None;
+    animation_state.current_spinning_reel = spinning.iter().position(|&s| s); // Start with the first reel that moves
     animation_state.target_results = target_columns.clone();
     animation_state.results = target_columns; // Set results immediately
-    animation_state.completed_reels = vec![false; 3];
+    animation_state.completed_reels = spinning.iter().map(|&s| !s).collect();
+    animation_state.spinning_reels = spinning.clone();
     
-    // Create circular reels that include our target symbols
-    // Each reel will be much longer and circular for smooth animation
-    let reel_length = 50; // Much longer reel for better circular effect
+    // Create circular reels from the machine's strips
+    // Each strip is repeated so the animation has a long loop, a strip position maps to the same
+    // symbol in every lap so landing on a stop shows the real window
+    let reel_length: usize = 50; // Much longer reel for better circular effect
     
-    // Initialize or reset columns
-    if animation_state.columns.is_empty() {
-        for i in 0..3 {
-            // Create a long circular reel with repeating base symbols
-            let reel_symbols = generate_circular_reel(&base_symbols, reel_length);
-            let mut column = SlotColumn::new(reel_symbols, i);
-            
-            // Set animation parameters for circular motion
+    if animation_state.columns.len() != slot_machine.cols() {
+        animation_state.columns = (0..slot_machine.cols())
+            .map(|i| SlotColumn::new(Vec::new(), i, slot_machine.rows()))
+            .collect();
+    }
+
+    let heights = animation_state.outcome.as_ref().map(|outcome| outcome.heights.clone()).unwrap_or_default();
+    for (i, column) in animation_state.columns.iter_mut().enumerate() {
+        column.visible_rows = heights.get(i).copied().unwrap_or(slot_machine.rows());
+        let reel = &slot_machine.reels()[i];
+        let strip_symbols: Vec<Symbol> = reel.strip().iter().map(|stop| stop.symbol.clone()).collect();
+        let laps = reel_length.div_ceil(strip_symbols.len()).max(1);
+        let new_reel_symbols = generate_circular_reel(&strip_symbols, strip_symbols.len() * laps);
+        
+        // Start from where the reel is resting (or the top on the first spin)
+        if column.reel_symbols.len() != new_reel_symbols.len() {
             column.current_offset = 0.0;
-            column.target_offset = (column.symbol_height * 3.0) + (i as f32 * 10.0); // Minimal rotation for 1s
-            column.spin_speed = 500.0 + (i as f32 * 50.0); // Optimized speeds: 400, 450, 500
-            animation_state.columns.push(column);
         }
-    } else {
-        // Reset existing columns for new animation
-        for (i, column) in animation_state.columns.iter_mut().enumerate() {
-            // Regenerate circular reel
-            column.reel_symbols = generate_circular_reel(&base_symbols, reel_length);
-            column.current_offset = 0.0;
-            column.target_offset = (column.symbol_height * 3.0) + (i as f32 * 10.0);
-            column.spin_speed = 500.0 + (i as f32 * 50.0);
-            column.is_spinning = false;
+        column.reel_symbols = new_reel_symbols;
+        column.is_spinning = false;
+        
+        // Held reels stay on the window they're showing
+        if !spinning[i] {
+            column.target_offset = column.current_offset;
+            continue;
         }
+        
+        // Travel at least `min_travel` symbols (3 is the minimal rotation for 1s) and land on the chosen stop
+        let current_index = (column.current_offset / column.symbol_height) as usize;
+        let min_index = current_index + min_travel;
+        let extra = (stops[i] + reel.len() - min_index % reel.len()) % reel.len();
+        let target_index = (min_index + extra) % column.reel_symbols.len();
+        
+        column.target_offset = target_index as f32 * column.symbol_height;
+        // Optimized speeds: 500, 550, 600, ... Long strips travel further, so those reels speed up
+        // to still land in about a second
+        let travel = (min_index + extra - current_index) as f32 * column.symbol_height;
+        column.spin_speed = (500.0 + (i as f32 * 50.0)).max(travel);
     }
     
     // Start the first reel
-    if let Some(first_column) = animation_state.columns.get_mut(0) {
-        first_column.is_spinning = true;
+    if let Some(first_reel) = animation_state.current_spinning_reel {
+        animation_state.columns[first_reel].is_spinning = true;
     }
     
     game_state.is_spinning = true;
@@ -195,24 +395,89 @@ pub fn start_slot_animation(
     animation_state.animation_timer = Timer::from_seconds(0.1, TimerMode::Repeating);
 }
 
+// Play one hold-and-spin respin: the machine decides every unlocked cell up front, then each of
+// them spins on its own and lands one after another while the locked coins stay put
+pub fn start_respin_animation(
+    animation_state: &mut SlotAnimationState,
+    hold_round: &mut HoldAndSpinRound,
+    slot_machine: &SlotMachine,
+    game_rng: &mut GameRng,
+) {
+    let Some(board) = hold_round.board.as_mut() else { return };
+    // A finished board is waiting to pay out, there's nothing left to respin
+    if board.is_over() {
+        return;
+    }
+
+    let mut spinning_cells = Vec::new();
+    for row in 0..board.grid.len() {
+        for col in 0..board.grid[row].len() {
+            if !board.is_locked(row, col) {
+                spinning_cells.push((row, col));
+            }
+        }
+    }
+
+    let new_coins = slot_machine.respin_with_rng(game_rng.rng(), board);
+    println!("🪙 Respin! {} new coins, {} respins left", new_coins.len(), board.respins_left);
+
+    animation_state.display_grid = Some(board.grid.clone());
+    animation_state.cell_spins = spinning_cells.into_iter()
+        .enumerate()
+        .map(|(i, (row, col))| CellSpin {
+            row,
+            col,
+            timer: Timer::from_seconds(0.5 + i as f32 * 0.12, TimerMode::Once),
+        })
+        .collect();
+}
+
+pub fn update_cell_spin_animation(
+    time: Res<Time>,
+    mut animation_state: ResMut<SlotAnimationState>,
+    mut game_state: ResMut<GameState>,
+    mut hold_round: ResMut<HoldAndSpinRound>,
+    mut popup_state: ResMut<WinPopupState>,
+    mut gamble: ResMut<GambleState>,
+) {
+    for spin in &mut animation_state.cell_spins {
+        spin.timer.tick(time.delta());
+    }
+    animation_state.cell_spins.retain(|spin| !spin.timer.finished());
+    if animation_state.is_busy() {
+        return;
+    }
+
+    // Every cell has landed, pay the coins once the round is over. A board that fills up as it
+    // triggers is over before any cell spins
+    let Some(board) = &hold_round.board else { return };
+    if !board.is_over() {
+        return;
+    }
+
+    let bet = hold_round.bet;
+    let total_win = board.total_value() * bet;
+    println!("🪙 Hold & spin over! {} coins worth ${:.2}", board.locked.iter().flatten().flatten().count(), total_win);
+    hold_round.board = None;
+
+    game_state.player_pool += total_win;
+    game_state.last_win_amount = total_win;
+    game_state.has_recent_win = true;
+    popup_state.pending_win = Some((total_win, total_win / bet));
+    popup_state.pending_bet = bet;
+    open_pending_win_popup(&mut popup_state, &mut gamble, bet);
+}
+
 pub fn update_slot_animation(
     time: Res<Time>,
     mut animation_state: ResMut<SlotAnimationState>,
     mut game_state: ResMut<GameState>,
-    slot_machine: Option<Res<SlotMachine>>,
     target_results: Option<Res<TargetResults>>,
-    mut commands: Commands,
-    mut popup_state: ResMut<crate::ui::slot_ui::WinPopupState>,
 ) {
     if !animation_state.is_animating {
         return;
     }
 
-    let slot_machine = match slot_machine {
-        Some(sm) => sm,
-        None => return,
-    };
-
     let target_results = match target_results {
         Some(tr) => tr,
         None => return,
@@ -232,8 +497,11 @@ pub fn update_slot_animation(
                     (max_offset - column.current_offset) + column.target_offset
                 };
                 
+                // A fast reel stops on the frame that would carry it past its target
                 let should_stop = column.is_spinning && 
-                    (distance_to_target < 15.0 || column.spin_speed < 120.0);
+                    (distance_to_target < 15.0
+                        || column.spin_speed < 120.0
+                        || distance_to_target <= column.spin_speed * time.delta_seconds());
                 
                 (should_stop, column.current_offset, column.target_offset)
             } else {
@@ -242,61 +510,52 @@ pub fn update_slot_animation(
         };
 
         // Now update the column
-        if let Some(column) = animation_state.columns.get_mut(current_reel) {
-            if column.is_spinning {
-                if !should_stop {
-                    // Update spinning animation with proper circular motion
-                    column.current_offset += column.spin_speed * time.delta_seconds();
-                    
-                    // Keep offset within bounds for circular effect
-                    let max_offset = column.reel_symbols.len() as f32 * column.symbol_height;
-                    if column.current_offset >= max_offset {
-                        column.current_offset -= max_offset; // Wrap around for circular effect
-                    }
-                    
-                    // Check if we should start slowing down
-                    let remaining_distance = if column.target_offset > column.current_offset {
-                        column.target_offset - column.current_offset
-                    } else {
-                        // Handle wrap-around case
-                        (max_offset - column.current_offset) + column.target_offset
-                    };
+        if let Some(column) = animation_state.columns.get_mut(current_reel)
+            && column.is_spinning
+        {
+            if !should_stop {
+                // Update spinning animation with proper circular motion
+                column.current_offset += column.spin_speed * time.delta_seconds();
+                
+                // Keep offset within bounds for circular effect
+                let max_offset = column.reel_symbols.len() as f32 * column.symbol_height;
+                if column.current_offset >= max_offset {
+                    column.current_offset -= max_offset; // Wrap around for circular effect
+                }
+                
+                // Check if we should start slowing down
+                let remaining_distance = if column.target_offset > column.current_offset {
+                    column.target_offset - column.current_offset
+                } else {
+                    // Handle wrap-around case
+                    (max_offset - column.current_offset) + column.target_offset
+                };
+                
+                // Quick deceleration for 1s timing, over the last fifth of a second of travel
+                if remaining_distance < column.spin_speed * 0.2 && column.spin_speed > 100.0 {
+                    // Fast deceleration for 1s target
+                    column.spin_speed *= 0.8;
                     
-                    // Quick deceleration for 1s timing
-                    if remaining_distance < 100.0 && column.spin_speed > 100.0 {
-                        // Fast deceleration for 1s target
-                        column.spin_speed *= 0.8;
-                        
-                        if column.spin_speed < 100.0 {
-                            column.spin_speed = 100.0;
-                        }
+                    if column.spin_speed < 100.0 {
+                        column.spin_speed = 100.0;
                     }
-                } else {
-                    // Stop the reel
-                    column.current_offset = column.target_offset;
-                    column.is_spinning = false;
-                    column.spin_speed = 0.0;
                 }
+            } else {
+                // Stop the reel
+                column.current_offset = column.target_offset;
+                column.is_spinning = false;
+                column.spin_speed = 0.0;
             }
         }
 
         // Handle reel completion (after releasing the mutable borrow of column)
         if should_stop {
-            // Position the reel to show our pre-determined target symbols
-            if let Some(target_column) = target_results.get(current_reel) {
-                if let Some(column) = animation_state.columns.get_mut(current_reel) {
-                    position_reel_to_show_symbols(column, target_column);
-                }
-            }
-            
             animation_state.completed_reels[current_reel] = true;
             
-            // Get the symbols that are now visible (should match our target)
-            let visible_symbols = if let Some(target_column) = target_results.get(current_reel) {
-                target_column.clone() // Use the exact target symbols instead of calculating from reel
-            } else {
-                Vec::new()
-            };
+            // The reel landed on its stop, so the visible window comes straight from the strip
+            let visible_symbols = animation_state.columns.get(current_reel)
+                .map(|column| get_visible_symbols_for_column(column, column.visible_rows))
+                .unwrap_or_default();
             
             // Get our expected target symbols for comparison
             let expected_symbols = if let Some(target_column) = target_results.get(current_reel) {
@@ -306,14 +565,8 @@ pub fn update_slot_animation(
             };
             
             println!("Reel {} stopped!", current_reel);
-            println!("  Expected: Top={}, Middle={}, Bottom={}", 
-                expected_symbols.get(0).map(|s| s.name.as_str()).unwrap_or("None"),
-                expected_symbols.get(1).map(|s| s.name.as_str()).unwrap_or("None"),
-                expected_symbols.get(2).map(|s| s.name.as_str()).unwrap_or("None"));
-            println!("  Actually showing: Top={}, Middle={}, Bottom={}", 
-                visible_symbols.get(0).map(|s| s.name.as_str()).unwrap_or("None"),
-                visible_symbols.get(1).map(|s| s.name.as_str()).unwrap_or("None"),
-                visible_symbols.get(2).map(|s| s.name.as_str()).unwrap_or("None"));
+            println!("  Expected (top to bottom): {:?}", expected_symbols.iter().map(|s| &s.name).collect::<Vec<_>>());
+            println!("  Actually showing: {:?}", visible_symbols.iter().map(|s| &s.name).collect::<Vec<_>>());
             
             // Check if they match
             let matches = expected_symbols.len() == visible_symbols.len() && 
@@ -321,10 +574,11 @@ pub fn update_slot_animation(
                     .all(|(expected, actual)| expected.name == actual.name);
             println!("  Symbols match: {}", matches);
             
-            // Start the next reel or finish animation
-            if current_reel < 2 {
+            // Start the next reel or finish animation (held reels are skipped)
+            let next_reel = (current_reel + 1..animation_state.spinning_reels.len())
+                .find(|&reel| animation_state.spinning_reels[reel]);
+            if let Some(next_reel) = next_reel {
                 // Start next reel
-                let next_reel = current_reel + 1;
                 animation_state.current_spinning_reel = Some(next_reel);
                 if let Some(next_column) = animation_state.columns.get_mut(next_reel) {
                     next_column.is_spinning = true;
@@ -336,77 +590,200 @@ pub fn update_slot_animation(
                 animation_state.is_animating = false;
                 game_state.is_spinning = false; // Reset the spinning state!
                 
-                println!("All reels stopped! Final results:");
+                println!("All reels stopped! Final results (top to bottom):");
                 for (i, column_result) in animation_state.results.iter().enumerate() {
                     println!("Column {}: {:?}", i, column_result.iter().map(|s| &s.name).collect::<Vec<_>>());
-                    if column_result.len() >= 3 {
-                        println!("  Layout - Top: {}, Middle: {}, Bottom: {}", 
-                            column_result[0].name, column_result[1].name, column_result[2].name);
-                    }
                 }
                 
-                // Convert to grid format for win checking
-                let mut final_grid = vec![Vec::new(); 3];
-                for row_idx in 0..3 {
-                    for col_idx in 0..3 {
-                        if let Some(column) = animation_state.results.get(col_idx) {
-                            if let Some(symbol) = column.get(row_idx) {
-                                final_grid[row_idx].push(symbol.clone());
-                            }
-                        }
-                    }
+                // Mystery symbols and wild modifiers changed the grid the reels landed on
+                let Some(outcome) = &animation_state.outcome else { return };
+                let modifiers = &outcome.modifiers;
+                let has_mystery = !modifiers.mystery.is_empty();
+                if has_mystery || !modifiers.sticky.is_empty() || !modifiers.expanded.is_empty() {
+                    animation_state.display_grid = Some(outcome.initial_grid().to_vec());
                 }
-                
-                // Check for wins (horizontal and diagonal only - no vertical for circular reels)
-                let wins = slot_machine.as_ref().check_wins(&final_grid);
-                
-                // Store wins in game state for the process_spin_results function
-                game_state.last_wins.clear();
-                for win in &wins {
-                    let simple_line = SimpleWinningLine {
-                        symbols: win.symbols.iter().map(|s| (*s).clone()).collect(),
-                        line_type: win.line_type.clone(),
-                    };
-                    game_state.last_wins.push(simple_line);
+                if has_mystery {
+                    animation_state.start_mystery_reveal();
                 }
                 
-                if !wins.is_empty() {
-                    println!("WINS FOUND: {} winning lines!", wins.len());
-                    for win in &wins {
-                        println!("Win: {:?} - symbols: {:?}", 
-                            win.line_type, 
-                            win.symbols.iter().map(|s| &s.name).collect::<Vec<_>>()
-                        );
-                    }
-                    
-                    // Calculate and add winnings immediately
-                    let total_win: f32 = game_state.last_wins.iter()
-                        .map(|line| line.symbols[0].multiplier * game_state.current_bet + line.symbols[0].addition)
-                        .sum();
-                    
-                    println!("🎉 WIN! Bet: ${:.2}, Multiplier calculation: ${:.2}", game_state.current_bet, total_win);
-                    println!("🎉 Pool before win: ${:.2}", game_state.player_pool);
-                    game_state.player_pool += total_win;
-                    println!("🎉 Pool after win: ${:.2}", game_state.player_pool);
-                    
-                    // Calculate multiplier for display
-                    let multiplier = total_win / game_state.current_bet;
-                    
-                    // Store pending win popup to show after bloom animation completes
-                    println!("🎯 Storing pending win popup! Amount: ${:.2}, Multiplier: {:.1}x", total_win, multiplier);
-                    popup_state.pending_win = Some((total_win, multiplier));
-                    
-                    // Store the win amount and mark as recent win
-                    game_state.last_win_amount = total_win;
-                    game_state.has_recent_win = true;
-                } else {
-                    println!("❌ No wins this time. Pool remains: ${:.2}", game_state.player_pool);
-                    // Mark that there's no recent win, but keep the last win amount displayed
-                    game_state.has_recent_win = false;
-                }
+                // Wins are settled by settle_spin_outcome once any reveal is over
+                animation_state.settle_pending = true;
+            }
+        }
+    }
+}
+
+// Settle the outcome the slot machine produced when the spin started, once the reels have
+// stopped and any mystery symbols have been revealed
+pub fn settle_spin_outcome(
+    mut animation_state: ResMut<SlotAnimationState>,
+    mut game_state: ResMut<GameState>,
+    slot_machine: Res<SlotMachine>,
+    mut popup_state: ResMut<WinPopupState>,
+    features: BonusFeatures,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let BonusFeatures { mut free_spins, mut jackpot, mut hold_round, mut hold_nudge, mut gamble } = features;
+
+    if !animation_state.settle_pending || animation_state.is_revealing() {
+        return;
+    }
+    animation_state.settle_pending = false;
+
+    let Some(outcome) = animation_state.outcome.clone() else { return };
+    let final_grid = outcome.initial_grid().to_vec();
+    
+    // The bloom animation starts with the first step's wins, cascades follow after it
+    game_state.last_wins = outcome.steps[0].wins.clone();
+    if outcome.steps.len() > 1 {
+        animation_state.cascade_phase = CascadePhase::Blooming;
+        animation_state.display_grid = Some(final_grid.clone());
+    }
+    
+    game_state.sticky_wilds = outcome.sticky_wilds.clone();
+    
+    // Free spins pay at the bet locked in when the round started, with the round's multiplier
+    let (bet, win_multiplier) = if free_spins.spin_is_free {
+        (free_spins.bet, free_spins.multiplier)
+    } else {
+        (game_state.current_bet, 1.0)
+    };
+    
+    // A jackpot combination pays its whole pool on top of the line wins
+    let jackpot_win = match jackpot.check(&final_grid, slot_machine.paylines()) {
+        Some(tier) => {
+            let won = jackpot.award(tier);
+            println!("💎 {} JACKPOT! ${:.2}", tier.label(), won);
+            if let Err(err) = save_jackpot(&jackpot) {
+                println!("⚠️ Failed to save jackpot: {}", err);
             }
+            won
         }
+        None => 0.0,
+    };
+    
+    // Wins can be gambled, except during free spins
+    gamble.allowed = !free_spins.spin_is_free;
+    
+    if outcome.has_wins() || jackpot_win > 0.0 {
+        for (index, step) in outcome.steps.iter().enumerate() {
+            println!("WINS FOUND (step {}, x{}): {} winning lines!", index, step.multiplier, step.wins.len());
+            for win in &step.wins {
+                println!("Win: {:?} - symbols: {:?} - pays as {} x{} (wild multiplier x{}, {} ways)", 
+                    win.line_type, 
+                    win.symbols.iter().map(|s| &s.name).collect::<Vec<_>>(),
+                    win.symbol.name,
+                    win.count,
+                    win.multiplier,
+                    win.ways
+                );
+            }
+        }
+        
+        // Calculate and add winnings immediately (all cascades included)
+        let total_win = outcome_payout(&outcome, bet) * win_multiplier + jackpot_win;
+        if free_spins.spin_is_free {
+            free_spins.accumulated_win += total_win;
+        }
+        
+        println!("🎉 WIN! Bet: ${:.2}, Multiplier calculation: ${:.2}", bet, total_win);
+        println!("🎉 Pool before win: ${:.2}", game_state.player_pool);
+        game_state.player_pool += total_win;
+        println!("🎉 Pool after win: ${:.2}", game_state.player_pool);
+        
+        // Calculate multiplier for display
+        let multiplier = total_win / bet;
+        
+        // Store pending win popup to show after bloom animation completes
+        println!("🎯 Storing pending win popup! Amount: ${:.2}, Multiplier: {:.1}x", total_win, multiplier);
+        popup_state.pending_win = Some((total_win, multiplier));
+        popup_state.pending_bet = bet;
+        if game_state.last_wins.is_empty() {
+            // Nothing to bloom, so the popup can show straight away
+            open_pending_win_popup(&mut popup_state, &mut gamble, bet);
+        }
+        
+        // Store the win amount and mark as recent win
+        game_state.last_win_amount = total_win;
+        game_state.has_recent_win = true;
+    } else {
+        println!("❌ No wins this time. Pool remains: ${:.2}", game_state.player_pool);
+        // Mark that there's no recent win, but keep the last win amount displayed
+        game_state.has_recent_win = false;
+    }
+    
+    // Scatters can start (or retrigger) the free spins round
+    if let Some(config) = slot_machine.free_spins() {
+        let awarded = slot_machine.free_spins_awarded(&final_grid);
+        if awarded > 0 {
+            free_spins.award(awarded, game_state.current_bet, config);
+        }
+    }
+    free_spins.finish_spin();
+    
+    // Enough coins lock in and start the hold-and-spin respins
+    if let Some(board) = slot_machine.hold_and_spin_trigger(game_rng.rng(), &final_grid) {
+        hold_round.start(board, bet);
+        animation_state.display_grid = Some(final_grid.clone());
+    }
+    
+    // Holds and nudges only follow a losing spin, a win ends any that are left
+    let losing = !outcome.has_wins() && jackpot_win == 0.0 && !free_spins.active && !hold_round.is_active();
+    if !losing {
+        hold_nudge.clear();
+    } else if !hold_nudge.nudging {
+        let offer = slot_machine.roll_hold_nudge(game_rng.rng());
+        hold_nudge.offer(offer, slot_machine.reels().len());
+    }
+    hold_nudge.nudging = false;
+}
+
+pub fn update_mystery_reveal(
+    time: Res<Time>,
+    mut animation_state: ResMut<SlotAnimationState>,
+) {
+    if !animation_state.revealing {
+        return;
+    }
+
+    animation_state.reveal_timer.tick(time.delta());
+    if animation_state.reveal_timer.finished() {
+        animation_state.revealing = false;
+    }
+}
+
+// Drops the symbols in during a cascade, then hands over to the bloom animation (or the win popup)
+pub fn update_cascade_animation(
+    time: Res<Time>,
+    mut animation_state: ResMut<SlotAnimationState>,
+    mut game_state: ResMut<GameState>,
+    mut popup_state: ResMut<WinPopupState>,
+    mut gamble: ResMut<GambleState>,
+) {
+    if animation_state.cascade_phase != CascadePhase::Falling {
+        return;
+    }
+
+    animation_state.fall_timer.tick(time.delta());
+    if !animation_state.fall_timer.finished() {
+        return;
     }
+
+    animation_state.fall_rows.clear();
+    let wins = animation_state.outcome.as_ref()
+        .and_then(|outcome| outcome.steps.get(animation_state.cascade_index))
+        .map(|step| step.wins.clone())
+        .unwrap_or_default();
+
+    if wins.is_empty() {
+        // Nothing left to highlight, the cascade chain is over
+        animation_state.cascade_phase = CascadePhase::Idle;
+        let bet = popup_state.pending_bet;
+        open_pending_win_popup(&mut popup_state, &mut gamble, bet);
+    } else {
+        animation_state.cascade_phase = CascadePhase::Blooming;
+    }
+    game_state.last_wins = wins;
 }
 
 pub fn get_visible_symbols_for_column(
@@ -429,26 +806,3 @@ pub fn get_visible_symbols_for_column(
     
     visible_symbols
 }
-
-// Helper function to position a reel to show specific target symbols
-fn position_reel_to_show_symbols(column: &mut SlotColumn, target_symbols: &[Symbol]) {
-    if target_symbols.is_empty() || column.reel_symbols.is_empty() {
-        return;
-    }
-    
-    // Simply replace the first 3 symbols in the reel with our target symbols
-    // This ensures we can always find them at position 0
-    for (i, symbol) in target_symbols.iter().enumerate().take(3) {
-        if i < column.reel_symbols.len() {
-            column.reel_symbols[i] = symbol.clone();
-        }
-    }
-    
-    // Set the offset to 0 to show these symbols at the top
-    // This ensures perfect alignment with the cell boundaries
-    column.current_offset = 0.0;
-    column.target_offset = 0.0;
-    
-    println!("Positioned reel to show target symbols at offset 0.0. Target symbols: {:?}", 
-        target_symbols.iter().take(3).map(|s| &s.name).collect::<Vec<_>>());
-}
\ No newline at end of file
@@ -22,13 +22,17 @@ impl Plugin for SlotUIPlugin {
             .init_resource::<GameState>()
             .init_resource::<WinBloomState>()
             .init_resource::<WinPopupState>()
+            .init_resource::<FreeSpinsState>()
+            .init_resource::<HoldAndSpinRound>()
+            .init_resource::<HoldNudgeState>()
+            .init_resource::<GambleState>()
             // Setup systems
             .add_systems(Startup, setup_ui)
             // Update systems
             .add_systems(Update, (
                 handle_spin_button,
                 update_displays,
-                update_slot_display_animation,
+                (update_slot_display_animation, resize_reel_cells),
                 update_spin_button_text,
                 process_spin_results,
                 start_win_bloom_animation,
@@ -36,6 +40,13 @@ impl Plugin for SlotUIPlugin {
                 show_win_popup,
                 animate_coin_flood,
                 update_win_popup,
+                update_free_spins_display,
+                show_free_spins_summary,
+                update_free_spins_summary,
+                update_jackpot_display,
+                handle_hold_nudge_buttons,
+                update_hold_nudge_buttons,
+                (show_gamble_panel, handle_gamble_buttons),
             ));
     }
 }
\ No newline at end of file
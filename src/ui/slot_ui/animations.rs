@@ -1,5 +1,8 @@
 use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
 use super::components::*;
+use crate::ui::slot_animation::{SlotAnimationState, CascadePhase};
+use crate::core::gamble::{GambleGuess, Suit};
 use rand::Rng;
 
 pub fn start_win_bloom_animation(
@@ -17,60 +20,54 @@ pub fn start_win_bloom_animation(
         
         println!("🌟 Starting win bloom animation for {} lines!", game_state.last_wins.len());
         
-        // Add WinningCell components to the winning cells
+        // Bloom the cells line by line using each win's cell coordinates.
+        // A cell shared by several lines only blooms once, with the first line that uses it
+        let mut sequence: Vec<(usize, usize, usize)> = Vec::new(); // (line_index, row, col)
         for (line_index, win_line) in game_state.last_wins.iter().enumerate() {
-            // For horizontal lines, the positions are predictable
-            match win_line.line_type {
-                crate::entities::slot_machine::LineType::Horizontal(row) => {
-                    for col in 0..3 {
-                        for (entity, cell) in &cell_query {
-                            if cell.row == row && cell.col == col {
-                                commands.entity(entity).insert(WinningCell {
-                                    timer: Timer::from_seconds(0.3, TimerMode::Once),
-                                    bloom_phase: BloomPhase::WaitingToStart,
-                                    line_index,
-                                    cell_index: col,
-                                });
-                            }
-                        }
-                    }
+            for &(row, col) in &win_line.cells {
+                if !sequence.iter().any(|&(_, r, c)| r == row && c == col) {
+                    sequence.push((line_index, row, col));
                 }
-                crate::entities::slot_machine::LineType::Diagonal(diag_type) => {
-                    let positions = if diag_type == 0 {
-                        vec![(0, 0), (1, 1), (2, 2)] // Top-left to bottom-right
-                    } else {
-                        vec![(0, 2), (1, 1), (2, 0)] // Top-right to bottom-left
-                    };
-                    
-                    for (cell_index, (row, col)) in positions.iter().enumerate() {
-                        for (entity, cell) in &cell_query {
-                            if cell.row == *row && cell.col == *col {
-                                commands.entity(entity).insert(WinningCell {
-                                    timer: Timer::from_seconds(0.3, TimerMode::Once),
-                                    bloom_phase: BloomPhase::WaitingToStart,
-                                    line_index,
-                                    cell_index,
-                                });
-                            }
-                        }
-                    }
+            }
+        }
+        bloom_state.total_cells = sequence.len();
+        
+        // Add WinningCell components to the winning cells
+        for (cell_index, (line_index, row, col)) in sequence.into_iter().enumerate() {
+            for (entity, cell) in &cell_query {
+                if cell.row == row && cell.col == col {
+                    commands.entity(entity).insert(WinningCell {
+                        timer: Timer::from_seconds(0.3, TimerMode::Once),
+                        bloom_phase: BloomPhase::WaitingToStart,
+                        line_index,
+                        cell_index,
+                    });
                 }
-                _ => {}
             }
         }
     }
 }
 
+/// The winning cells being bloomed and the overlays spawned on top of them
+#[derive(SystemParam)]
+pub struct BloomCells<'w, 's> {
+    commands: Commands<'w, 's>,
+    winning_cells: Query<'w, 's, (Entity, &'static mut WinningCell), With<WinningCell>>,
+    overlay_query: Query<'w, 's, &'static mut BackgroundColor, With<BloomOverlay>>,
+    cell_children: Query<'w, 's, &'static Children>,
+}
+
 pub fn update_win_bloom_animation(
     time: Res<Time>,
     mut bloom_state: ResMut<WinBloomState>,
-    game_state: Res<GameState>,
-    mut winning_cells: Query<(Entity, &mut WinningCell), With<WinningCell>>,
-    mut overlay_query: Query<&mut BackgroundColor, With<BloomOverlay>>,
-    cell_children: Query<&Children>,
-    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+    mut animation_state: ResMut<SlotAnimationState>,
+    bloom_cells: BloomCells,
     mut popup_state: ResMut<WinPopupState>,
+    mut gamble: ResMut<GambleState>,
 ) {
+    let BloomCells { mut commands, mut winning_cells, mut overlay_query, cell_children } = bloom_cells;
+
     if !bloom_state.is_active {
         return;
     }
@@ -84,8 +81,8 @@ pub fn update_win_bloom_animation(
         match winning_cell.bloom_phase {
             BloomPhase::WaitingToStart => {
                 // Check if it's this cell's turn to start blooming
-                if winning_cell.line_index == bloom_state.current_line 
-                    && winning_cell.cell_index == bloom_state.current_cell {
+                if winning_cell.cell_index == bloom_state.current_cell {
+                    bloom_state.current_line = winning_cell.line_index;
                     winning_cell.bloom_phase = BloomPhase::SequentialBloom;
                     winning_cell.timer.reset();
                     
@@ -112,10 +109,6 @@ pub fn update_win_bloom_animation(
                     
                     // Move to next cell
                     bloom_state.current_cell += 1;
-                    if bloom_state.current_cell >= 3 {
-                        bloom_state.current_cell = 0;
-                        bloom_state.current_line += 1;
-                    }
                 }
             }
             BloomPhase::SequentialBloom => {
@@ -158,7 +151,7 @@ pub fn update_win_bloom_animation(
     // Check if all animations are complete
     let all_finished = winning_cells.iter().all(|(_, cell)| cell.bloom_phase == BloomPhase::Finished);
     
-    if all_finished && bloom_state.current_line >= game_state.last_wins.len() {
+    if all_finished && bloom_state.current_cell >= bloom_state.total_cells {
         // Clean up
         bloom_state.is_active = false;
         
@@ -177,20 +170,43 @@ pub fn update_win_bloom_animation(
         
         println!("🌟 Win bloom animation completed!");
         
-        // Check if there's a pending win popup to show
-        if let Some((win_amount, multiplier)) = popup_state.pending_win.take() {
-            println!("🎉 Now showing delayed win popup! Amount: ${:.2}, Multiplier: {:.1}x", win_amount, multiplier);
-            popup_state.is_active = true;
-            popup_state.win_amount = win_amount;
-            popup_state.multiplier = multiplier;
-            popup_state.popup_timer = Timer::from_seconds(3.0, TimerMode::Once);
-            popup_state.coin_spawn_timer = Timer::from_seconds(0.01, TimerMode::Repeating);
-            popup_state.coins_spawned = 0;
-            popup_state.max_coins = (win_amount / game_state.current_bet * 10.0).min(200.0) as u32; // Many more coins!
+        // Cascades drop the next symbols in before the win popup
+        if animation_state.cascade_phase == CascadePhase::Blooming && animation_state.has_next_cascade() {
+            game_state.last_wins.clear();
+            animation_state.start_cascade_fall();
+        } else {
+            animation_state.cascade_phase = CascadePhase::Idle;
+            let bet = popup_state.pending_bet;
+            open_pending_win_popup(&mut popup_state, &mut gamble, bet);
         }
     }
 }
 
+// The bloom (and cascade) animations are over: offer the win to the gamble if it's allowed,
+// otherwise show the popup straight away
+pub fn open_pending_win_popup(popup_state: &mut WinPopupState, gamble: &mut GambleState, bet: f32) {
+    if gamble.allowed && !gamble.active && popup_state.pending_win.is_some() {
+        let (win_amount, _) = popup_state.pending_win.take().unwrap();
+        gamble.offer(win_amount, bet);
+        return;
+    }
+    show_pending_win_popup(popup_state, bet);
+}
+
+// Show the win popup that was waiting, with whatever amount was finally collected
+pub fn show_pending_win_popup(popup_state: &mut WinPopupState, bet: f32) {
+    if let Some((win_amount, multiplier)) = popup_state.pending_win.take() {
+        println!("🎉 Now showing delayed win popup! Amount: ${:.2}, Multiplier: {:.1}x", win_amount, multiplier);
+        popup_state.is_active = true;
+        popup_state.win_amount = win_amount;
+        popup_state.multiplier = multiplier;
+        popup_state.popup_timer = Timer::from_seconds(3.0, TimerMode::Once);
+        popup_state.coin_spawn_timer = Timer::from_seconds(0.01, TimerMode::Repeating);
+        popup_state.coins_spawned = 0;
+        popup_state.max_coins = (win_amount / bet * 10.0).min(200.0) as u32; // Many more coins!
+    }
+}
+
 pub fn show_win_popup(
     mut commands: Commands,
     mut popup_state: ResMut<WinPopupState>,
@@ -314,6 +330,157 @@ pub fn show_win_popup(
     }
 }
 
+// Gamble panel over the reels while a win is being gambled
+pub fn show_gamble_panel(
+    mut commands: Commands,
+    gamble: Res<GambleState>,
+    existing_panel: Query<Entity, With<GamblePanel>>,
+    mut text_query: Query<&mut Text, With<GambleText>>,
+) {
+    if !gamble.active {
+        for entity in &existing_panel {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    if !existing_panel.is_empty() {
+        let status = gamble_status(&gamble);
+        for mut text in &mut text_query {
+            if text.sections[0].value != status {
+                text.sections[0].value = status.clone();
+            }
+        }
+        return;
+    }
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.7).into(),
+                z_index: ZIndex::Local(100),
+                ..default()
+            },
+            GamblePanel,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(30.0)),
+                        row_gap: Val::Px(12.0),
+                        ..default()
+                    },
+                    background_color: Color::srgba(0.1, 0.3, 0.15, 0.95).into(), // Card table green
+                    border_radius: BorderRadius::all(Val::Px(20.0)),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "🃏 GAMBLE 🃏",
+                        TextStyle {
+                            font_size: 48.0,
+                            color: Color::srgb(1.0, 0.84, 0.0),
+                            ..default()
+                        },
+                    ));
+                    parent.spawn((
+                        TextBundle::from_section(
+                            gamble_status(&gamble),
+                            TextStyle {
+                                font_size: 24.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        ),
+                        GambleText,
+                    ));
+
+                    // Red/black doubles the stake
+                    spawn_gamble_row(parent, |parent| {
+                        spawn_gamble_button(parent, "RED x2", Color::srgb(0.8, 0.1, 0.1), GambleButton { guess: GambleGuess::Red });
+                        spawn_gamble_button(parent, "BLACK x2", Color::srgb(0.15, 0.15, 0.15), GambleButton { guess: GambleGuess::Black });
+                    });
+
+                    // The suit quadruples it
+                    spawn_gamble_row(parent, |parent| {
+                        for suit in Suit::ALL {
+                            let color = if suit.is_red() {
+                                Color::srgb(0.6, 0.15, 0.15)
+                            } else {
+                                Color::srgb(0.25, 0.25, 0.3)
+                            };
+                            spawn_gamble_button(parent, &format!("{} x4", suit.label()), color, GambleButton { guess: GambleGuess::Suit(suit) });
+                        }
+                    });
+
+                    spawn_gamble_row(parent, |parent| {
+                        spawn_gamble_button(parent, "COLLECT", Color::srgb(0.8, 0.6, 0.0), CollectButton);
+                    });
+                });
+        });
+}
+
+fn gamble_status(gamble: &GambleState) -> String {
+    let last = match &gamble.last {
+        Some(outcome) if outcome.won => format!("{} - you won!\n", outcome.card.label()),
+        Some(outcome) => format!("{} - you lost\n", outcome.card.label()),
+        None => String::new(),
+    };
+    format!("{}STAKE: ${:.2}   ({} of {} gambles left)", last, gamble.stake, gamble.max_rounds - gamble.rounds, gamble.max_rounds)
+}
+
+fn spawn_gamble_row(parent: &mut ChildBuilder, children: impl FnOnce(&mut ChildBuilder)) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(children);
+}
+
+fn spawn_gamble_button(parent: &mut ChildBuilder, label: &str, color: Color, marker: impl Component) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::axes(Val::Px(14.0), Val::Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: color.into(),
+                border_radius: BorderRadius::all(Val::Px(8.0)),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
 pub fn animate_coin_flood(
     mut commands: Commands,
     time: Res<Time>,
@@ -451,4 +618,101 @@ pub fn update_win_popup(
 
         println!("🎰 Win popup closed!");
     }
-}
\ No newline at end of file
+}
+
+pub fn show_free_spins_summary(
+    mut commands: Commands,
+    free_spins: Res<FreeSpinsState>,
+    bloom_state: Res<WinBloomState>,
+    popup_state: Res<WinPopupState>,
+    gamble: Res<GambleState>,
+    existing_summary: Query<Entity, With<FreeSpinsSummary>>,
+) {
+    // Wait for the last free spin's bloom, gamble and win popup before showing the round total
+    if bloom_state.is_active || gamble.active || popup_state.is_active || popup_state.pending_win.is_some() {
+        return;
+    }
+    let Some((spins, bonus_total)) = free_spins.summary else { return };
+    if !existing_summary.is_empty() {
+        return;
+    }
+
+    println!("🎁 Showing free spins summary! {} spins, total ${:.2}", spins, bonus_total);
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.7).into(),
+                z_index: ZIndex::Local(100),
+                ..default()
+            },
+            FreeSpinsSummary {
+                timer: Timer::from_seconds(4.0, TimerMode::Once),
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(40.0)),
+                        ..default()
+                    },
+                    background_color: Color::srgba(0.1, 0.5, 0.3, 0.95).into(),
+                    border_radius: BorderRadius::all(Val::Px(20.0)),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "🎁 FREE SPINS COMPLETE 🎁",
+                        TextStyle {
+                            font_size: 48.0,
+                            color: Color::srgb(1.0, 0.84, 0.0),
+                            ..default()
+                        },
+                    ).with_style(Style {
+                        margin: UiRect::bottom(Val::Px(20.0)),
+                        ..default()
+                    }));
+                    parent.spawn(TextBundle::from_section(
+                        format!("{} spins played", spins),
+                        TextStyle {
+                            font_size: 28.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ));
+                    parent.spawn(TextBundle::from_section(
+                        format!("BONUS TOTAL: ${:.2}", bonus_total),
+                        TextStyle {
+                            font_size: 40.0,
+                            color: Color::srgb(0.6, 1.0, 0.6),
+                            ..default()
+                        },
+                    ));
+                });
+        });
+}
+
+pub fn update_free_spins_summary(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut free_spins: ResMut<FreeSpinsState>,
+    mut summary_query: Query<(Entity, &mut FreeSpinsSummary)>,
+) {
+    for (entity, mut summary) in &mut summary_query {
+        summary.timer.tick(time.delta());
+        if summary.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            free_spins.summary = None;
+        }
+    }
+}
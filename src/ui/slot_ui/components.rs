@@ -1,39 +1,25 @@
 use bevy::prelude::*;
-use crate::entities::slot_machine::{Symbol, SlotMachine, Reel};
+use bevy::ecs::system::SystemParam;
+use crate::entities::slot_machine::{Symbol, FreeSpinsConfig, WinningLine, HoldAndSpinBoard, HoldNudgeOffer, StickyWild};
+use crate::entities::jackpot::{Jackpot, JackpotTier};
+use crate::core::gamble::{GambleGuess, GambleOutcome};
 
 /// Main game state resource
 #[derive(Resource)]
 pub struct GameState {
-    pub slot_machine: SlotMachine,
     pub player_pool: f32,
     pub current_bet: f32,
     pub last_grid: Option<Vec<Vec<Symbol>>>,
-    pub last_wins: Vec<SimpleWinningLine>,
+    pub last_wins: Vec<WinningLine>,
     pub is_spinning: bool,
     pub last_win_amount: f32,
     pub has_recent_win: bool,
+    pub sticky_wilds: Vec<StickyWild>, // wilds staying on their cells for the next spins
 }
 
 impl Default for GameState {
     fn default() -> Self {
-        // Create default symbols
-        let symbols = vec![
-            Symbol::new("🍒", "Cherry", 2.0, 0.0, 50.0),
-            Symbol::new("🍋", "Lemon", 3.0, 0.0, 30.0),
-            Symbol::new("🔔", "Bell", 5.0, 0.0, 15.0),
-            Symbol::new("⭐", "Star", 10.0, 0.0, 4.0),
-            Symbol::new("7️⃣", "Seven", 20.0, 0.0, 1.0),
-        ];
-        
-        // Create 3 identical reels
-        let reels = vec![
-            Reel::new(symbols.clone()),
-            Reel::new(symbols.clone()),
-            Reel::new(symbols),
-        ];
-        
         Self {
-            slot_machine: SlotMachine::new(reels),
             player_pool: 100.0,
             current_bet: 5.0,
             last_grid: None,
@@ -41,15 +27,221 @@ impl Default for GameState {
             is_spinning: false,
             last_win_amount: 0.0,
             has_recent_win: false,
+            sticky_wilds: Vec::new(),
         }
     }
 }
 
-/// Simplified winning line for UI purposes
-#[derive(Clone)]
-pub struct SimpleWinningLine {
-    pub symbols: Vec<Symbol>,
-    pub line_type: crate::entities::slot_machine::LineType,
+/// Free spins bonus round state, kept alongside GameState
+#[derive(Resource)]
+pub struct FreeSpinsState {
+    pub active: bool,
+    pub remaining: u32,
+    pub total_awarded: u32,
+    pub accumulated_win: f32,
+    pub multiplier: f32,
+    pub bet: f32, // bet locked in when the round was triggered
+    pub spin_is_free: bool, // whether the spin in progress is a free spin
+    pub summary: Option<(u32, f32)>, // (spins played, bonus total) waiting to be shown
+}
+
+impl Default for FreeSpinsState {
+    fn default() -> Self {
+        Self {
+            active: false,
+            remaining: 0,
+            total_awarded: 0,
+            accumulated_win: 0.0,
+            multiplier: 1.0,
+            bet: 0.0,
+            spin_is_free: false,
+            summary: None,
+        }
+    }
+}
+
+impl FreeSpinsState {
+    // Called when a spin starts, uses up a free spin if there is one
+    pub fn start_spin(&mut self) -> bool {
+        self.spin_is_free = self.remaining > 0;
+        if self.spin_is_free {
+            self.remaining -= 1;
+        }
+        self.spin_is_free
+    }
+
+    // Start a new round, or add spins to the current one if retriggers are allowed. A round never
+    // awards more than the machine's max_spins in total
+    pub fn award(&mut self, spins: u32, bet: f32, config: &FreeSpinsConfig) {
+        if self.active && !config.retrigger {
+            return;
+        }
+        let spins = config.capped(if self.active { self.total_awarded } else { 0 }, spins);
+        if spins == 0 {
+            println!("🎁 No more free spins, the round is at its limit of {}", config.max_spins);
+        } else if !self.active {
+            self.active = true;
+            self.remaining = spins;
+            self.total_awarded = spins;
+            self.accumulated_win = 0.0;
+            self.multiplier = config.multiplier;
+            self.bet = bet;
+            println!("🎁 {} FREE SPINS awarded! Wins x{}", spins, config.multiplier);
+        } else {
+            self.remaining += spins;
+            self.total_awarded += spins;
+            println!("🎁 Retrigger! +{} free spins ({} left)", spins, self.remaining);
+        }
+    }
+
+    // Called once the spin has been paid out, ends the round after the last free spin
+    pub fn finish_spin(&mut self) {
+        if self.spin_is_free && self.remaining == 0 && self.active {
+            self.active = false;
+            self.summary = Some((self.total_awarded, self.accumulated_win));
+            println!("🎁 Free spins over! Bonus total: ${:.2}", self.accumulated_win);
+        }
+        self.spin_is_free = false;
+    }
+}
+
+/// Hold-and-spin bonus round state, the board itself lives in the slot machine's model
+#[derive(Resource, Default)]
+pub struct HoldAndSpinRound {
+    pub board: Option<HoldAndSpinBoard>,
+    pub bet: f32, // bet locked in when the round was triggered
+}
+
+impl HoldAndSpinRound {
+    pub fn is_active(&self) -> bool {
+        self.board.is_some()
+    }
+
+    pub fn start(&mut self, board: HoldAndSpinBoard, bet: f32) {
+        println!("🪙 HOLD & SPIN! {} coins locked, {} respins", board.locked.iter().flatten().flatten().count(), board.respins_left);
+        self.board = Some(board);
+        self.bet = bet;
+    }
+
+    // Cash value of the coin locked at a cell, in currency
+    pub fn locked_value(&self, row: usize, col: usize) -> Option<f32> {
+        let board = self.board.as_ref()?;
+        board.locked.get(row)?.get(col).copied().flatten().map(|value| value * self.bet)
+    }
+}
+
+/// Holds and nudges on offer between spins
+#[derive(Resource, Default)]
+pub struct HoldNudgeState {
+    pub holds_offered: bool,
+    pub held: Vec<bool>, // reels the player chose to hold for the next spin
+    pub nudges: u32,
+    pub nudging: bool, // the reels are moving because of a nudge, not a spin
+    pub spin_was_held: bool, // holds are never offered twice in a row
+}
+
+impl HoldNudgeState {
+    // Called when a spin starts: returns the held reels and uses up whatever was on offer
+    pub fn start_spin(&mut self, reels: usize) -> Vec<bool> {
+        let held = if self.holds_offered && self.held.len() == reels {
+            self.held.clone()
+        } else {
+            vec![false; reels]
+        };
+        self.spin_was_held = held.contains(&true);
+        self.clear();
+        held
+    }
+
+    pub fn offer(&mut self, offer: HoldNudgeOffer, reels: usize) {
+        self.holds_offered = offer.holds && !self.spin_was_held;
+        self.held = vec![false; reels];
+        self.nudges = offer.nudges;
+        if self.holds_offered || self.nudges > 0 {
+            println!("✋ Holds: {}, nudges: {}", self.holds_offered, self.nudges);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.holds_offered = false;
+        self.held.iter_mut().for_each(|held| *held = false);
+        self.nudges = 0;
+    }
+
+    // Toggle a hold, at least one reel always has to spin
+    pub fn toggle_hold(&mut self, reel: usize) -> bool {
+        if !self.holds_offered || reel >= self.held.len() {
+            return false;
+        }
+        let holding_others = self.held.iter().enumerate().filter(|&(i, &held)| i != reel && held).count();
+        if !self.held[reel] && holding_others + 1 >= self.held.len() {
+            return false;
+        }
+        self.held[reel] = !self.held[reel];
+        true
+    }
+}
+
+/// Gamble (double-up) offered between the bloom animation and the win popup
+#[derive(Resource)]
+pub struct GambleState {
+    pub allowed: bool, // whether the current spin's win may be gambled (not during bonus rounds)
+    pub active: bool,
+    pub stake: f32, // the win currently at risk
+    pub bet: f32,
+    pub rounds: u32,
+    pub max_rounds: u32,
+    pub last: Option<GambleOutcome>,
+    pub history: Vec<GambleOutcome>, // every gamble this session, in draw order
+}
+
+impl Default for GambleState {
+    fn default() -> Self {
+        Self {
+            allowed: false,
+            active: false,
+            stake: 0.0,
+            bet: 0.0,
+            rounds: 0,
+            max_rounds: 5,
+            last: None,
+            history: Vec::new(),
+        }
+    }
+}
+
+impl GambleState {
+    pub fn offer(&mut self, stake: f32, bet: f32) {
+        println!("🃏 Gamble ${:.2}? (up to {} times)", stake, self.max_rounds);
+        self.active = true;
+        self.stake = stake;
+        self.bet = bet;
+        self.rounds = 0;
+        self.last = None;
+    }
+
+    pub fn record(&mut self, outcome: GambleOutcome) {
+        println!("🃏 Gamble #{}: {} on ${:.2}, card {} -> {}",
+            self.history.len() + 1,
+            outcome.guess.label(),
+            outcome.stake,
+            outcome.card.label(),
+            if outcome.won { "WIN" } else { "LOSE" });
+        self.stake = outcome.payout();
+        self.rounds += 1;
+        self.last = Some(outcome);
+        self.history.push(outcome);
+    }
+}
+
+/// Every bonus feature a spin can start, carry on or end, for the systems that spin and settle
+#[derive(SystemParam)]
+pub struct BonusFeatures<'w> {
+    pub free_spins: ResMut<'w, FreeSpinsState>,
+    pub jackpot: ResMut<'w, Jackpot>,
+    pub hold_round: ResMut<'w, HoldAndSpinRound>,
+    pub hold_nudge: ResMut<'w, HoldNudgeState>,
+    pub gamble: ResMut<'w, GambleState>,
 }
 
 /// Bloom animation phases
@@ -67,6 +259,7 @@ pub struct WinBloomState {
     pub is_active: bool,
     pub current_line: usize,
     pub current_cell: usize,
+    pub total_cells: usize,
     pub flash_timer: Timer,
     pub flash_count: u32,
     pub max_flashes: u32,
@@ -79,6 +272,7 @@ impl Default for WinBloomState {
             is_active: false,
             current_line: 0,
             current_cell: 0,
+            total_cells: 0,
             flash_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
             flash_count: 0,
             max_flashes: 5,
@@ -98,6 +292,7 @@ pub struct WinPopupState {
     pub coins_spawned: u32,
     pub max_coins: u32,
     pub pending_win: Option<(f32, f32)>, // (win_amount, multiplier) - will show popup after bloom finishes
+    pub pending_bet: f32, // the bet the pending win was settled at, a free spin's is the round's locked in bet
 }
 
 impl Default for WinPopupState {
@@ -111,6 +306,7 @@ impl Default for WinPopupState {
             coins_spawned: 0,
             max_coins: 150,
             pending_win: None,
+            pending_bet: 0.0,
         }
     }
 }
@@ -181,4 +377,43 @@ pub struct WinText;
 pub struct MultiplierText;
 
 #[derive(Component)]
-pub struct CelebrationOverlay;
\ No newline at end of file
+pub struct CelebrationOverlay;
+
+#[derive(Component)]
+pub struct FreeSpinsDisplay;
+
+#[derive(Component)]
+pub struct GamblePanel;
+
+#[derive(Component)]
+pub struct GambleText;
+
+#[derive(Component)]
+pub struct GambleButton {
+    pub guess: GambleGuess,
+}
+
+#[derive(Component)]
+pub struct CollectButton;
+
+#[derive(Component)]
+pub struct HoldButton {
+    pub column_index: usize,
+}
+
+#[derive(Component)]
+pub struct NudgeButton {
+    pub column_index: usize,
+}
+
+/// Header readout for one jackpot tier, counting up towards the real pool value
+#[derive(Component)]
+pub struct JackpotDisplay {
+    pub tier: JackpotTier,
+    pub shown_value: f32,
+}
+
+#[derive(Component)]
+pub struct FreeSpinsSummary {
+    pub timer: Timer,
+}
\ No newline at end of file
@@ -1,15 +1,40 @@
 use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
 use super::components::*;
-use crate::entities::slot_machine::Symbol;
+use crate::entities::slot_machine::{Symbol, SlotMachine};
+use crate::entities::jackpot::Jackpot;
 use crate::ui::assets::{SymbolAssets, get_symbol_texture};
 use crate::ui::slot_animation::SlotAnimationState;
 use crate::ui::bet_controls::BetInputField;
 
+type PoolText = (With<PoolDisplay>, Without<BetDisplay>, Without<WinDisplay>, Without<BetInputField>);
+type BetText = (With<BetDisplay>, Without<PoolDisplay>, Without<WinDisplay>, Without<BetInputField>);
+type InputText = (With<BetInputField>, Without<PoolDisplay>, Without<BetDisplay>, Without<WinDisplay>);
+type WinText = (With<WinDisplay>, Without<PoolDisplay>, Without<BetDisplay>, Without<BetInputField>);
+type CellImage = (&'static mut UiImage, &'static mut Visibility);
+type CellImageOnly = (With<SlotCellImage>, Without<Text>);
+type HoldNudgeButtonParts = (
+    &'static mut Visibility,
+    &'static mut BackgroundColor,
+    &'static Children,
+    Option<&'static HoldButton>,
+    Option<&'static NudgeButton>,
+);
+type HoldOrNudgeButton = Or<(With<HoldButton>, With<NudgeButton>)>;
+
+/// The text, image and layout of the children a slot cell draws its symbol with
+#[derive(SystemParam)]
+pub struct CellParts<'w, 's> {
+    texts: Query<'w, 's, &'static mut Text, Without<SlotCellImage>>,
+    styles: Query<'w, 's, &'static mut Style>,
+    images: Query<'w, 's, CellImage, CellImageOnly>,
+}
+
 pub fn update_displays(
-    mut pool_query: Query<&mut Text, (With<PoolDisplay>, Without<BetDisplay>, Without<WinDisplay>, Without<BetInputField>)>,
-    mut bet_query: Query<&mut Text, (With<BetDisplay>, Without<PoolDisplay>, Without<WinDisplay>, Without<BetInputField>)>,
-    input_query: Query<(&Text, &BetInputField), (With<BetInputField>, Without<PoolDisplay>, Without<BetDisplay>, Without<WinDisplay>)>,
-    mut win_query: Query<&mut Text, (With<WinDisplay>, Without<PoolDisplay>, Without<BetDisplay>, Without<BetInputField>)>,
+    mut pool_query: Query<&mut Text, PoolText>,
+    mut bet_query: Query<&mut Text, BetText>,
+    input_query: Query<(&Text, &BetInputField), InputText>,
+    mut win_query: Query<&mut Text, WinText>,
     game_state: Res<GameState>,
 ) {
     // Update pool display
@@ -39,68 +64,127 @@ pub fn update_displays(
 }
 
 pub fn update_slot_display_animation(
-    mut cell_query: Query<(&Children, &SlotCell, &mut BackgroundColor)>,
-    mut text_query: Query<&mut Text, Without<SlotCellImage>>,
-    mut style_query: Query<&mut Style>,
-    mut image_query: Query<(&mut UiImage, &mut Visibility), (With<SlotCellImage>, Without<Text>)>,
+    mut cell_query: Query<(Entity, &Children, &SlotCell, &mut BackgroundColor, &mut Transform)>,
+    mut cell_parts: CellParts,
     animation_state: Res<SlotAnimationState>,
+    hold_round: Res<HoldAndSpinRound>,
+    game_state: Res<GameState>,
     symbol_assets: Option<Res<SymbolAssets>>,
 ) {
     // Handle both spinning AND stopped reels using the same logic for consistent alignment
-    for (children, cell, mut bg_color) in &mut cell_query {
+    for (cell_entity, children, cell, mut bg_color, mut transform) in &mut cell_query {
         let column_index = cell.col;
         
         // Get the column animation data from SlotAnimationState
         if let Some(column) = animation_state.columns.get(column_index) {
-            // Always use get_visible_symbols_for_column for consistent alignment
-            let visible_symbols = crate::ui::slot_animation::get_visible_symbols_for_column(
-                column, 
-                3
-            );
+            // Respinning cells cycle on their own and sticky wilds stay put while the reels spin.
+            // During cascades and respins the grid comes from the outcome, otherwise from the reel strip
+            let cell_spin_symbol = animation_state.cell_spin_symbol(cell.row, column_index).cloned();
+            let cell_is_respinning = cell_spin_symbol.is_some();
+            let sticky_symbol = animation_state.sticky_symbol(cell.row, column_index).cloned();
+            let is_sticky = sticky_symbol.is_some() || (!animation_state.is_animating && game_state.sticky_wilds.iter()
+                .any(|wild| wild.row == cell.row && wild.col == column_index));
+            let is_expanded = animation_state.is_expanded(cell.row, column_index);
+            // Mystery cells keep showing the mystery symbol until they flip halfway
+            let display_grid = if animation_state.is_mystery_face_down(cell.row, column_index) {
+                None
+            } else {
+                animation_state.display_grid.as_ref()
+            };
+            let symbol = match (cell_spin_symbol, sticky_symbol, display_grid) {
+                (Some(symbol), _, _) => Some(symbol),
+                (None, Some(symbol), _) => Some(symbol),
+                (None, None, Some(grid)) => grid.get(cell.row).and_then(|row| row.get(column_index)).cloned(),
+                (None, None, None) => crate::ui::slot_animation::get_visible_symbols_for_column(column, column.visible_rows)
+                    .get(cell.row)
+                    .cloned(),
+            };
+
+            let scale = if animation_state.is_mystery_cell(cell.row, column_index) {
+                animation_state.reveal_scale()
+            } else {
+                1.0
+            };
+            if transform.scale.x != scale {
+                transform.scale.x = scale;
+            }
+            // Locked hold-and-spin coins show their cash value
+            let locked_value = if cell_is_respinning {
+                None
+            } else {
+                hold_round.locked_value(cell.row, column_index)
+            };
             
-            if let Some(symbol) = visible_symbols.get(cell.row) {
+            // Cells drop from above while a cascade is falling
+            let fall_rows = animation_state.fall_rows.get(cell.row)
+                .and_then(|row| row.get(column_index))
+                .copied()
+                .unwrap_or(0);
+            let fall_offset = -(fall_rows as f32) * column.symbol_height * (1.0 - animation_state.fall_timer.fraction());
+            if let Ok(mut style) = cell_parts.styles.get_mut(cell_entity)
+                && style.top != Val::Px(fall_offset)
+            {
+                style.top = Val::Px(fall_offset);
+            }
+            
+            if let Some(symbol) = symbol {
                 // Update the display
                 let mut image_child = None;
                 let mut text_child = None;
                 
                 for &child in children.iter() {
-                    if image_query.get_mut(child).is_ok() {
+                    if cell_parts.images.get_mut(child).is_ok() {
                         image_child = Some(child);
-                    } else if text_query.get_mut(child).is_ok() {
+                    } else if cell_parts.texts.get_mut(child).is_ok() {
                         text_child = Some(child);
                     }
                 }
                 
                 // Try to use image assets first, fall back to emoji
                 let mut used_image = false;
-                if let (Some(assets), Some(image_entity)) = (&symbol_assets, image_child) {
+                if let (Some(assets), Some(image_entity)) = (&symbol_assets, image_child)
+                    && let Ok((mut image, mut visibility)) = cell_parts.images.get_mut(image_entity)
+                {
                     if let Some(texture) = get_symbol_texture(assets, &symbol.name) {
-                        if let Ok((mut image, mut visibility)) = image_query.get_mut(image_entity) {
-                            image.texture = texture;
-                            *visibility = Visibility::Visible;
-                            used_image = true;
-                        }
+                        image.texture = texture;
+                        *visibility = Visibility::Visible;
+                        used_image = true;
+                    } else {
+                        // No texture for this symbol, don't leave the previous one showing
+                        *visibility = Visibility::Hidden;
                     }
                 }
                 
                 // Update text
                 if let Some(text_entity) = text_child {
-                    if let Ok(mut text) = text_query.get_mut(text_entity) {
-                        if used_image {
+                    if let Ok(mut text) = cell_parts.texts.get_mut(text_entity) {
+                        if let Some(value) = locked_value {
+                            text.sections[0].value = format!("${:.0}", value);
+                            text.sections[0].style.font_size = 28.0;
+                        } else if used_image {
                             text.sections[0].value = "".to_string();
                         } else {
                             text.sections[0].value = symbol.icon.clone();
                         }
+                        if locked_value.is_none() && text.sections[0].style.font_size != 50.0 {
+                            text.sections[0].style.font_size = 50.0;
+                        }
                     }
                     
                     // Reset padding when animation starts (remove question mark padding)
-                    if let Ok(mut style) = style_query.get_mut(text_entity) {
+                    if let Ok(mut style) = cell_parts.styles.get_mut(text_entity) {
                         style.padding = UiRect::all(Val::Px(0.0));
                     }
                 }
                 
                 // Set background color based on spinning state
-                if column.is_spinning {
+                if locked_value.is_some() {
+                    *bg_color = Color::srgb(1.0, 0.84, 0.3).into(); // Gold for locked coins
+                } else if is_sticky {
+                    *bg_color = Color::srgb(0.8, 0.65, 1.0).into(); // Purple for sticky wilds
+                } else if is_expanded {
+                    *bg_color = Color::srgb(0.6, 0.95, 0.9).into(); // Teal where a wild expanded
+                } else if column.is_spinning || cell_is_respinning {
                     *bg_color = Color::srgb(0.85, 0.85, 0.95).into(); // Light blue tint while spinning
                 } else {
                     *bg_color = Color::WHITE.into(); // Normal color when stopped
@@ -113,7 +197,7 @@ pub fn update_slot_display_animation(
 pub fn update_slot_display_final(
     mut cell_query: Query<(&Children, &SlotCell, &mut BackgroundColor)>,
     mut text_query: Query<&mut Text, Without<SlotCellImage>>,
-    mut image_query: Query<(&mut UiImage, &mut Visibility), (With<SlotCellImage>, Without<Text>)>,
+    mut image_query: Query<CellImage, CellImageOnly>,
     animation_state: Res<SlotAnimationState>,
     game_state: Res<GameState>,
     symbol_assets: Option<Res<SymbolAssets>>,
@@ -130,7 +214,7 @@ pub fn update_slot_display_final(
         
         // Priority: Use animation results if available and complete, otherwise fallback to game state
         let symbol = if !animation_state.results.is_empty() && 
-                       animation_state.results.len() == 3 && // All 3 columns completed
+                       animation_state.results.len() == animation_state.columns.len() && // All columns completed
                        column_index < animation_state.results.len() &&
                        row_index < animation_state.results[column_index].len() {
             // Use final animation results (these should match the game state exactly)
@@ -166,47 +250,138 @@ pub fn update_slot_display_final(
         };
 
         // Update image display if assets are available
-        if let (Some(image_entity), Some(assets)) = (image_child, &symbol_assets) {
-            if let Ok((mut ui_image, mut visibility)) = image_query.get_mut(image_entity) {
-                match symbol.name.as_str() {
-                    "Cherry" => {
-                        ui_image.texture = assets.cherry.clone();
-                        *visibility = Visibility::Visible;
-                    }
-                    "Lemon" => {
-                        ui_image.texture = assets.lemon.clone();
-                        *visibility = Visibility::Visible;
-                    }
-                    "Bell" => {
-                        ui_image.texture = assets.bell.clone();
-                        *visibility = Visibility::Visible;
-                    }
-                    "Star" => {
-                        ui_image.texture = assets.star.clone();
-                        *visibility = Visibility::Visible;
-                    }
-                    "Seven" => {
-                        ui_image.texture = assets.seven.clone();
-                        *visibility = Visibility::Visible;
-                    }
-                    _ => {
-                        *visibility = Visibility::Hidden;
-                    }
+        if let (Some(image_entity), Some(assets)) = (image_child, &symbol_assets)
+            && let Ok((mut ui_image, mut visibility)) = image_query.get_mut(image_entity)
+        {
+            match symbol.name.as_str() {
+                "Cherry" => {
+                    ui_image.texture = assets.cherry.clone();
+                    *visibility = Visibility::Visible;
+                }
+                "Lemon" => {
+                    ui_image.texture = assets.lemon.clone();
+                    *visibility = Visibility::Visible;
+                }
+                "Bell" => {
+                    ui_image.texture = assets.bell.clone();
+                    *visibility = Visibility::Visible;
+                }
+                "Star" => {
+                    ui_image.texture = assets.star.clone();
+                    *visibility = Visibility::Visible;
+                }
+                "Seven" => {
+                    ui_image.texture = assets.seven.clone();
+                    *visibility = Visibility::Visible;
+                }
+                _ => {
+                    *visibility = Visibility::Hidden;
                 }
             }
         }
 
         // Update text display (only show if no asset is available)
-        if let Some(text_entity) = text_child {
-            if let Ok(mut text) = text_query.get_mut(text_entity) {
-                text.sections[0].value = symbol.icon.clone();
-                // Hide text if we're showing an image, otherwise show it
-                text.sections[0].style.color = if has_asset { 
-                    Color::NONE  // Transparent when image is shown
-                } else { 
-                    Color::BLACK  // Visible when no image
-                };
+        if let Some(text_entity) = text_child
+            && let Ok(mut text) = text_query.get_mut(text_entity)
+        {
+            text.sections[0].value = symbol.icon.clone();
+            // Hide text if we're showing an image, otherwise show it
+            text.sections[0].style.color = if has_asset { 
+                Color::NONE  // Transparent when image is shown
+            } else { 
+                Color::BLACK  // Visible when no image
+            };
+        }
+    }
+}
+
+// Spin button label while the reels are idle
+pub fn idle_spin_label(free_spins: &FreeSpinsState, hold_round: &HoldAndSpinRound) -> String {
+    if let Some(board) = &hold_round.board {
+        format!("RESPIN ({})", board.respins_left)
+    } else if free_spins.remaining > 0 {
+        format!("FREE SPIN ({})", free_spins.remaining)
+    } else {
+        "SPIN!".to_string()
+    }
+}
+
+pub fn update_free_spins_display(
+    mut display_query: Query<&mut Text, With<FreeSpinsDisplay>>,
+    free_spins: Res<FreeSpinsState>,
+) {
+    if let Ok(mut text) = display_query.get_single_mut() {
+        let value = if free_spins.active {
+            format!(
+                "🎁 FREE SPINS: {} / {}  (x{})   BONUS WIN: ${:.2}",
+                free_spins.remaining,
+                free_spins.total_awarded,
+                free_spins.multiplier,
+                free_spins.accumulated_win
+            )
+        } else {
+            String::new()
+        };
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+pub fn update_jackpot_display(
+    time: Res<Time>,
+    jackpot: Option<Res<Jackpot>>,
+    mut display_query: Query<(&mut Text, &mut JackpotDisplay)>,
+) {
+    let Some(jackpot) = jackpot else { return };
+
+    for (mut text, mut display) in &mut display_query {
+        let target = jackpot.value(display.tier);
+        if display.shown_value < target {
+            // Tick up towards the pool, faster the further behind it is
+            let step = ((target - display.shown_value) * 3.0).max(0.5) * time.delta_seconds();
+            display.shown_value = (display.shown_value + step).min(target);
+        } else {
+            // A won pool drops straight back to its seed value
+            display.shown_value = target;
+        }
+
+        let value = format!("${:.2}", display.shown_value);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+pub fn update_hold_nudge_buttons(
+    mut button_query: Query<HoldNudgeButtonParts, HoldOrNudgeButton>,
+    mut text_query: Query<&mut Text>,
+    hold_nudge: Res<HoldNudgeState>,
+) {
+    for (mut visibility, mut color, children, hold, nudge) in &mut button_query {
+        let (visible, label, background) = if let Some(hold) = hold {
+            let held = hold_nudge.held.get(hold.column_index).copied().unwrap_or(false);
+            if held {
+                (hold_nudge.holds_offered, "HELD".to_string(), Color::srgb(0.2, 0.7, 0.3))
+            } else {
+                (hold_nudge.holds_offered, "HOLD".to_string(), Color::srgb(0.4, 0.4, 0.5))
             }
+        } else if nudge.is_some() {
+            (hold_nudge.nudges > 0, format!("NUDGE {}", hold_nudge.nudges), Color::srgb(0.8, 0.5, 0.1))
+        } else {
+            continue;
+        };
+
+        let new_visibility = if visible { Visibility::Inherited } else { Visibility::Hidden };
+        if *visibility != new_visibility {
+            *visibility = new_visibility;
+        }
+        *color = background.into();
+        if let Some(child) = children.first()
+            && let Ok(mut text) = text_query.get_mut(*child)
+            && text.sections[0].value != label
+        {
+            text.sections[0].value = label;
         }
     }
 }
@@ -216,20 +391,46 @@ pub fn update_spin_button_text(
     mut text_query: Query<&mut Text>,
     game_state: Res<GameState>,
     animation_state: Res<SlotAnimationState>,
+    free_spins: Res<FreeSpinsState>,
+    hold_round: Res<HoldAndSpinRound>,
 ) {
-    if let Ok(children) = button_query.get_single() {
-        if let Some(child) = children.first() {
-            if let Ok(mut text) = text_query.get_mut(*child) {
-                if !game_state.is_spinning && !animation_state.is_animating {
-                    if text.sections[0].value != "SPIN!" {
-                        text.sections[0].value = "SPIN!".to_string();
-                    }
-                } else if game_state.is_spinning || animation_state.is_animating {
-                    if text.sections[0].value != "SPINNING..." {
-                        text.sections[0].value = "SPINNING...".to_string();
-                    }
-                }
+    if let Ok(children) = button_query.get_single()
+        && let Some(child) = children.first()
+        && let Ok(mut text) = text_query.get_mut(*child)
+    {
+        let spinning = game_state.is_spinning || animation_state.is_animating || animation_state.is_respinning();
+        if !spinning {
+            let label = idle_spin_label(&free_spins, &hold_round);
+            if text.sections[0].value != label {
+                text.sections[0].value = label;
             }
+        } else if text.sections[0].value != "SPINNING..." {
+            text.sections[0].value = "SPINNING...".to_string();
+        }
+    }
+}
+
+// Megaways reels show a different number of symbols every spin: the cells a reel shows share
+// its height and the rest are hidden
+pub fn resize_reel_cells(
+    mut cell_query: Query<(&SlotCell, &mut Style)>,
+    animation_state: Res<SlotAnimationState>,
+    slot_machine: Res<SlotMachine>,
+) {
+    if slot_machine.megaways().is_none() {
+        return;
+    }
+
+    for (cell, mut style) in &mut cell_query {
+        let Some(column) = animation_state.columns.get(cell.col) else { continue };
+        let (display, height) = if cell.row < column.visible_rows {
+            (Display::Flex, Val::Px(super::setup::cell_height(column.visible_rows)))
+        } else {
+            (Display::None, style.height)
+        };
+        if style.display != display || style.height != height {
+            style.display = display;
+            style.height = height;
         }
     }
-}
\ No newline at end of file
+}
@@ -1,39 +1,84 @@
 use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
 use super::components::*;
+use super::displays::idle_spin_label;
+use super::animations::show_pending_win_popup;
 use crate::entities::slot_machine::SlotMachine;
-use crate::ui::slot_animation::{SlotAnimationState, start_slot_animation};
+use crate::core::rng::GameRng;
+use crate::core::gamble;
+use crate::util::json::save_jackpot;
+use crate::ui::slot_animation::{SlotAnimationState, start_slot_animation, start_respin_animation, start_nudge_animation};
 use crate::ui::bet_controls::{BetUpButton, BetDownButton, BetInputField};
 
+type SpinButtonInteraction = (&'static Interaction, &'static mut BackgroundColor, &'static Children);
+type SpinButtonChanged = (Changed<Interaction>, With<SpinButton>);
+type HoldNudgeInteraction = (&'static Interaction, Option<&'static HoldButton>, Option<&'static NudgeButton>);
+type HoldNudgeChanged = (Changed<Interaction>, Or<(With<HoldButton>, With<NudgeButton>)>);
+type GambleInteraction = (&'static Interaction, Option<&'static GambleButton>, Option<&'static CollectButton>);
+type GambleChanged = (Changed<Interaction>, Or<(With<GambleButton>, With<CollectButton>)>);
+
+/// The spin button and the texts its label can be found in
+#[derive(SystemParam)]
+pub struct SpinButtonQuery<'w, 's> {
+    interactions: Query<'w, 's, SpinButtonInteraction, SpinButtonChanged>,
+    texts: Query<'w, 's, &'static mut Text>,
+}
+
 pub fn handle_spin_button(
-    mut commands: Commands,
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &Children),
-        (Changed<Interaction>, With<SpinButton>),
-    >,
-    mut text_query: Query<&mut Text>,
+    commands: Commands,
+    mut button: SpinButtonQuery,
     mut game_state: ResMut<GameState>,
     mut animation_state: ResMut<SlotAnimationState>,
     slot_machine: Res<SlotMachine>,
+    mut game_rng: ResMut<GameRng>,
+    features: BonusFeatures,
 ) {
+    let BonusFeatures { mut free_spins, mut jackpot, mut hold_round, mut hold_nudge, gamble } = features;
+
+    // The reels stay put until the gamble is collected or lost
+    if gamble.active {
+        return;
+    }
+
     let mut should_start_spin = false;
+    let mut should_respin = false;
     
-    for (interaction, mut color, children) in &mut interaction_query {
+    for (interaction, mut color, children) in &mut button.interactions {
         match *interaction {
+            Interaction::Pressed if hold_round.is_active() => {
+                // Respins are part of the bonus and don't cost anything
+                if !animation_state.is_busy() {
+                    should_respin = true;
+                    *color = Color::srgb(0.6, 0.1, 0.1).into();
+                }
+            }
             Interaction::Pressed => {
-                if !game_state.is_spinning && !animation_state.is_animating && game_state.player_pool >= game_state.current_bet {
+                let can_afford = free_spins.remaining > 0 || game_state.player_pool >= game_state.current_bet;
+                if !game_state.is_spinning && !animation_state.is_busy() && can_afford {
                     should_start_spin = true;
                     game_state.is_spinning = true;
                     
-                    // Debug output to verify bet deduction
-                    println!("💰 Spinning! Bet: ${:.2}, Pool before: ${:.2}", game_state.current_bet, game_state.player_pool);
-                    game_state.player_pool -= game_state.current_bet;
-                    println!("💰 Pool after bet: ${:.2}", game_state.player_pool);
+                    // Free spins don't cost anything
+                    if free_spins.start_spin() {
+                        println!("🎁 Free spin! {} left", free_spins.remaining);
+                    } else {
+                        // Debug output to verify bet deduction
+                        println!("💰 Spinning! Bet: ${:.2}, Pool before: ${:.2}", game_state.current_bet, game_state.player_pool);
+                        game_state.player_pool -= game_state.current_bet;
+                        println!("💰 Pool after bet: ${:.2}", game_state.player_pool);
+                        
+                        // A share of every paid bet feeds the progressive jackpots
+                        jackpot.contribute(game_state.current_bet);
+                        if let Err(err) = save_jackpot(&jackpot) {
+                            println!("⚠️ Failed to save jackpot: {}", err);
+                        }
+                    }
                     
                     // Update button text
-                    if let Some(child) = children.first() {
-                        if let Ok(mut text) = text_query.get_mut(*child) {
-                            text.sections[0].value = "SPINNING...".to_string();
-                        }
+                    if let Some(child) = children.first()
+                        && let Ok(mut text) = button.texts.get_mut(*child)
+                    {
+                        text.sections[0].value = "SPINNING...".to_string();
                     }
                     
                     *color = Color::srgb(0.6, 0.1, 0.1).into();
@@ -49,13 +94,13 @@ pub fn handle_spin_button(
                     *color = Color::srgb(0.8, 0.2, 0.2).into();
                 }
                 // Always ensure button text is correct when not spinning
-                if !game_state.is_spinning && !animation_state.is_animating {
-                    if let Some(child) = children.first() {
-                        if let Ok(mut text) = text_query.get_mut(*child) {
-                            if text.sections[0].value != "SPIN!" {
-                                text.sections[0].value = "SPIN!".to_string();
-                            }
-                        }
+                if !game_state.is_spinning && !animation_state.is_animating
+                    && let Some(child) = children.first()
+                    && let Ok(mut text) = button.texts.get_mut(*child)
+                {
+                    let label = idle_spin_label(&free_spins, &hold_round);
+                    if text.sections[0].value != label {
+                        text.sections[0].value = label;
                     }
                 }
             }
@@ -63,14 +108,101 @@ pub fn handle_spin_button(
     }
     
     // Start animation outside the loop to avoid borrow checker issues
-    if should_start_spin {
-        start_slot_animation(animation_state, game_state, slot_machine, commands);
+    if should_respin {
+        start_respin_animation(&mut animation_state, &mut hold_round, &slot_machine, &mut game_rng);
+    } else if should_start_spin {
+        let held = hold_nudge.start_spin(slot_machine.reels().len());
+        start_slot_animation(animation_state, game_state, slot_machine, game_rng, commands, held);
+    }
+}
+
+pub fn handle_hold_nudge_buttons(
+    commands: Commands,
+    interaction_query: Query<HoldNudgeInteraction, HoldNudgeChanged>,
+    game_state: ResMut<GameState>,
+    animation_state: ResMut<SlotAnimationState>,
+    mut hold_nudge: ResMut<HoldNudgeState>,
+    slot_machine: Res<SlotMachine>,
+) {
+    if game_state.is_spinning || animation_state.is_busy() {
+        return;
+    }
+
+    let mut nudge_reel = None;
+    for (interaction, hold, nudge) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(hold) = hold {
+            if hold_nudge.toggle_hold(hold.column_index) {
+                println!("✋ Reel {} held: {}", hold.column_index, hold_nudge.held[hold.column_index]);
+            }
+        } else if let Some(nudge) = nudge
+            && hold_nudge.nudges > 0
+        {
+            nudge_reel = Some(nudge.column_index);
+        }
+    }
+
+    // Start the nudge outside the loop, it takes the resources by value
+    if let Some(reel) = nudge_reel {
+        hold_nudge.nudges -= 1;
+        hold_nudge.nudging = true;
+        start_nudge_animation(animation_state, game_state, slot_machine, commands, reel);
+    }
+}
+
+pub fn handle_gamble_buttons(
+    interaction_query: Query<GambleInteraction, GambleChanged>,
+    mut gamble_state: ResMut<GambleState>,
+    mut game_state: ResMut<GameState>,
+    mut popup_state: ResMut<WinPopupState>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if !gamble_state.active {
+        return;
+    }
+
+    let mut collect = false;
+    for (interaction, guess_button, collect_button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(button) = guess_button {
+            // Same RNG as the reels, so a seeded session replays its gambles too
+            let outcome = gamble::gamble(game_rng.rng(), button.guess, gamble_state.stake);
+            // The win is already in the pool, only the difference moves
+            game_state.player_pool += outcome.payout() - outcome.stake;
+            gamble_state.record(outcome);
+
+            if !outcome.won {
+                gamble_state.active = false;
+                game_state.last_win_amount = 0.0;
+                game_state.has_recent_win = false;
+                return;
+            }
+            game_state.last_win_amount = gamble_state.stake;
+            collect = gamble_state.rounds >= gamble_state.max_rounds;
+        } else if collect_button.is_some() {
+            collect = true;
+        }
+        break;
+    }
+
+    if collect {
+        let (stake, bet) = (gamble_state.stake, gamble_state.bet);
+        println!("🃏 Collected ${:.2}", stake);
+        gamble_state.active = false;
+        popup_state.pending_win = Some((stake, stake / bet));
+        popup_state.pending_bet = bet;
+        show_pending_win_popup(&mut popup_state, bet);
     }
 }
 
 pub fn process_spin_results(
-    mut commands: Commands,
     mut game_state: ResMut<GameState>,
+    free_spins: Res<FreeSpinsState>,
+    hold_round: Res<HoldAndSpinRound>,
     mut button_query: Query<(&mut BackgroundColor, &Children), With<SpinButton>>,
     mut text_query: Query<&mut Text>,
     animation_state: Res<SlotAnimationState>,
@@ -82,43 +214,13 @@ pub fn process_spin_results(
         // Reset button appearance
         if let Ok((mut color, children)) = button_query.get_single_mut() {
             *color = Color::srgb(0.8, 0.2, 0.2).into();
-            if let Some(child) = children.first() {
-                if let Ok(mut text) = text_query.get_mut(*child) {
-                    text.sections[0].value = "SPIN!".to_string();
-                }
+            if let Some(child) = children.first()
+                && let Ok(mut text) = text_query.get_mut(*child)
+            {
+                text.sections[0].value = idle_spin_label(&free_spins, &hold_round);
             }
         }
         
-        // Check for wins if we have a grid
-        if let Some(grid) = game_state.last_grid.clone() {
-            let wins = game_state.slot_machine.check_wins(&grid);
-            
-            // Convert wins to simplified format
-            game_state.last_wins.clear();
-            for win in wins {
-                let simple_line = SimpleWinningLine {
-                    symbols: win.symbols.into_iter().cloned().collect(),
-                    line_type: win.line_type,
-                };
-                game_state.last_wins.push(simple_line);
-            }
-            
-            // Add winnings to pool
-            let total_win: f32 = game_state.last_wins.iter()
-                .map(|line| line.symbols[0].multiplier * game_state.current_bet + line.symbols[0].addition)
-                .sum();
-            
-            if total_win > 0.0 {
-                println!("🎉 WIN! Bet: ${:.2}, Multiplier calculation: ${:.2}", game_state.current_bet, total_win);
-                println!("🎉 Pool before win: ${:.2}", game_state.player_pool);
-                game_state.player_pool += total_win;
-                println!("🎉 Pool after win: ${:.2}", game_state.player_pool);
-                
-                // Win popup will be handled by the delayed mechanism after bloom completes
-                println!("🎯 Win detected, bloom animation will trigger popup after completion");
-            } else {
-                println!("❌ No wins this time. Pool remains: ${:.2}", game_state.player_pool);
-            }
-        }
+        // Wins and payouts are settled once by update_slot_animation when the last reel stops
     }
 }
\ No newline at end of file
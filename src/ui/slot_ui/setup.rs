@@ -2,8 +2,26 @@ use bevy::prelude::*;
 use bevy::core_pipeline::bloom::{BloomSettings, BloomCompositeMode};
 use super::components::*;
 use crate::ui::bet_controls::{BetUpButton, BetDownButton, BetInputField};
+use crate::entities::jackpot::JackpotTier;
+use crate::entities::slot_machine::SlotMachine;
 
-pub fn setup_ui(mut commands: Commands) {
+// Height of the reel window inside the grid, and the widest the grid may get in the window
+const REEL_HEIGHT: f32 = 340.0;
+const MAX_GRID_WIDTH: f32 = 720.0;
+
+// Side of one symbol cell: 90px on the classic 3x3, smaller when more rows or reels have to fit
+fn cell_size(rows: usize, cols: usize) -> f32 {
+    let by_height = cell_height(rows);
+    let by_width = (MAX_GRID_WIDTH - 70.0) / cols.max(1) as f32 - 20.0;
+    by_height.min(by_width).min(90.0)
+}
+
+// Tallest a cell can be with `rows` cells stacked in a reel
+pub fn cell_height(rows: usize) -> f32 {
+    REEL_HEIGHT / rows.max(1) as f32 - 10.0
+}
+
+pub fn setup_ui(mut commands: Commands, slot_machine: Res<SlotMachine>) {
     // Camera with enhanced bloom settings for visible effects
     commands.spawn((
         Camera2dBundle::default(),
@@ -22,6 +40,7 @@ pub fn setup_ui(mut commands: Commands) {
         is_active: false,
         current_line: 0,
         current_cell: 0,
+        total_cells: 0,
         flash_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
         flash_count: 0,
         max_flashes: 5,
@@ -38,6 +57,7 @@ pub fn setup_ui(mut commands: Commands) {
         coins_spawned: 0,
         max_coins: 150, // Many coins to cover almost entire background
         pending_win: None, // No pending win initially
+        pending_bet: 0.0,
     });
 
     // Root UI container
@@ -66,6 +86,24 @@ pub fn setup_ui(mut commands: Commands) {
                 },
             ));
 
+            create_jackpot_display(parent);
+
+            // Free spins counter, empty unless a free spins round is running
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 22.0,
+                        color: Color::srgb(0.4, 1.0, 0.6),
+                        ..default()
+                    },
+                ).with_style(Style {
+                    margin: UiRect::top(Val::Px(10.0)),
+                    ..default()
+                }),
+                FreeSpinsDisplay,
+            ));
+
             // Player info container
             parent
                 .spawn(NodeBundle {
@@ -86,7 +124,8 @@ pub fn setup_ui(mut commands: Commands) {
                     create_win_display(parent);
                 });
 
-            create_slot_grid(parent);
+            create_slot_grid(parent, slot_machine.rows(), slot_machine.cols());
+            create_hold_nudge_controls(parent, slot_machine.rows(), slot_machine.cols());
             create_spin_button(parent);
 
             // Game instructions
@@ -104,6 +143,62 @@ pub fn setup_ui(mut commands: Commands) {
         });
 }
 
+fn create_jackpot_display(parent: &mut ChildBuilder) {
+    // One readout per tier, Grand first and largest
+    let tiers = [
+        (JackpotTier::Grand, 26.0, Color::srgb(1.0, 0.3, 0.3)),
+        (JackpotTier::Major, 22.0, Color::srgb(0.8, 0.4, 1.0)),
+        (JackpotTier::Minor, 20.0, Color::srgb(0.3, 0.7, 1.0)),
+        (JackpotTier::Mini, 18.0, Color::srgb(0.4, 1.0, 0.6)),
+    ];
+
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::SpaceEvenly,
+                align_items: AlignItems::Center,
+                margin: UiRect::top(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (tier, font_size, color) in tiers {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            tier.label(),
+                            TextStyle {
+                                font_size: 14.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        ));
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "$0.00",
+                                TextStyle {
+                                    font_size,
+                                    color,
+                                    ..default()
+                                },
+                            ),
+                            JackpotDisplay { tier, shown_value: 0.0 },
+                        ));
+                    });
+            }
+        });
+}
+
 fn create_balance_display(parent: &mut ChildBuilder) {
     parent
         .spawn(NodeBundle {
@@ -303,14 +398,16 @@ fn create_win_display(parent: &mut ChildBuilder) {
         });
 }
 
-fn create_slot_grid(parent: &mut ChildBuilder) {
-    // Slot machine grid - now organized by columns for animation
+fn create_slot_grid(parent: &mut ChildBuilder, rows: usize, cols: usize) {
+    // Slot machine grid - organized by columns for animation, one per reel
+    let cell = cell_size(rows, cols);
+    let scale = cell / 90.0;
     parent
         .spawn((
             NodeBundle {
                 style: Style {
-                    width: Val::Px(400.0),
-                    height: Val::Px(400.0),
+                    width: Val::Px(cols as f32 * (cell + 20.0) + 70.0),
+                    height: Val::Px(REEL_HEIGHT + 60.0),
                     flex_direction: FlexDirection::Row, // Horizontal layout for columns
                     justify_content: JustifyContent::SpaceEvenly,
                     align_items: AlignItems::Center,
@@ -325,14 +422,13 @@ fn create_slot_grid(parent: &mut ChildBuilder) {
             SlotGrid,
         ))
         .with_children(|parent| {
-            // Create 3 columns
-            for col in 0..3 {
+            for col in 0..cols {
                 parent
                     .spawn((
                         NodeBundle {
                             style: Style {
-                                width: Val::Px(110.0),
-                                height: Val::Px(340.0),
+                                width: Val::Px(cell + 20.0),
+                                height: Val::Px(REEL_HEIGHT),
                                 flex_direction: FlexDirection::Column,
                                 justify_content: JustifyContent::SpaceEvenly,
                                 align_items: AlignItems::Center,
@@ -346,13 +442,13 @@ fn create_slot_grid(parent: &mut ChildBuilder) {
                         SlotColumnContainer { column_index: col },
                     ))
                     .with_children(|parent| {
-                        // Create 3 visible slots per column
-                        for row in 0..3 {
+                        // One slot per visible row
+                        for row in 0..rows {
                             parent.spawn((
                                 NodeBundle {
                                     style: Style {
-                                        width: Val::Px(90.0),
-                                        height: Val::Px(90.0),
+                                        width: Val::Px(cell),
+                                        height: Val::Px(cell),
                                         justify_content: JustifyContent::Center,
                                         align_items: AlignItems::Center,
                                         margin: UiRect::all(Val::Px(5.0)),
@@ -368,8 +464,8 @@ fn create_slot_grid(parent: &mut ChildBuilder) {
                                 parent.spawn((
                                     ImageBundle {
                                         style: Style {
-                                            width: Val::Px(70.0),
-                                            height: Val::Px(70.0),
+                                            width: Val::Px(70.0 * scale),
+                                            height: Val::Px(70.0 * scale),
                                             align_self: AlignSelf::Center,
                                             justify_self: JustifySelf::Center,
                                             margin: UiRect::all(Val::Auto),
@@ -383,13 +479,13 @@ fn create_slot_grid(parent: &mut ChildBuilder) {
                                 
                                 parent.spawn(TextBundle {
                                     style: Style {
-                                        padding: UiRect::right(Val::Px(65.0)),
+                                        padding: UiRect::right(Val::Px(65.0 * scale)),
                                         ..default()
                                     },
                                     text: Text::from_section(
                                         "?",
                                         TextStyle {
-                                            font_size: 50.0,
+                                            font_size: 50.0 * scale,
                                             color: Color::BLACK,
                                             ..default()
                                         },
@@ -403,6 +499,71 @@ fn create_slot_grid(parent: &mut ChildBuilder) {
         });
 }
 
+fn create_hold_nudge_controls(parent: &mut ChildBuilder, rows: usize, cols: usize) {
+    // HOLD and NUDGE buttons under each reel, hidden until a losing spin offers them
+    let column_width = cell_size(rows, cols) + 20.0;
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(cols as f32 * column_width + 70.0),
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::SpaceEvenly,
+                padding: UiRect::horizontal(Val::Px(20.0)),
+                margin: UiRect::top(Val::Px(-20.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for col in 0..cols {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Px(column_width),
+                            justify_content: JustifyContent::SpaceBetween,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        let button_width = (column_width / 2.0 - 3.0).min(52.0);
+                        spawn_reel_button(parent, "HOLD", button_width, HoldButton { column_index: col });
+                        spawn_reel_button(parent, "NUDGE", button_width, NudgeButton { column_index: col });
+                    });
+            }
+        });
+}
+
+fn spawn_reel_button(parent: &mut ChildBuilder, label: &str, width: f32, marker: impl Component) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(width),
+                    height: Val::Px(30.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgb(0.4, 0.4, 0.5).into(),
+                border_radius: BorderRadius::all(Val::Px(6.0)),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 12.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
 fn create_spin_button(parent: &mut ChildBuilder) {
     parent
         .spawn((
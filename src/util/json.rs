@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::fs::OpenOptions;
 use std::io::Read;
@@ -5,33 +6,34 @@ use std::io::Result;
 use std::io::Write; 
 use std::path::Path; 
 use crate::entities::player::Player;
+use crate::entities::jackpot::Jackpot;
+use crate::entities::slot_machine::SlotMachine;
 
 const FILE_PATH: &str = "player.json";
+const JACKPOT_FILE_PATH: &str = "jackpot.json";
 
 pub fn get_player() -> Player {
     let file_path = Path::new(FILE_PATH);
 
-    if file_path.exists() {
-        if let Ok(file) = File::open(file_path) {
-            if let Ok(player) = serde_json::from_reader(file) {
-                return player; 
-            }
-        }
+    if file_path.exists()
+        && let Ok(file) = File::open(file_path)
+        && let Ok(player) = serde_json::from_reader(file)
+    {
+        return player; 
     }
  
     // If file doesn't exist (or fails to parse), make a default Player
     let default_player = Player::new(0, 0, 0);
 
-    if let Ok(json) = serde_json::to_string_pretty(&default_player) {
-        if let Ok(mut file) = OpenOptions::new()
+    if let Ok(json) = serde_json::to_string_pretty(&default_player)
+        && let Ok(mut file) = OpenOptions::new()
             .write(true)
             .create(true)
             .truncate(true) // overwrite if it exists
             .open(file_path)
-        {
-            let _ = file.write_all(json.as_bytes());
-        }
-    }   
+    {
+        let _ = file.write_all(json.as_bytes());
+    }
     default_player
 }
 
@@ -88,4 +90,66 @@ pub fn update_revive(value: u32)-> Result<()> {
     let mut file = OpenOptions::new().write(true).truncate(true).open(FILE_PATH)?;
     file.write_all(new_json.as_bytes())?;
     Ok(())
-}
\ No newline at end of file
+}
+
+// Every machine's jackpot pools, keyed by the machine name the game was started with
+fn get_jackpots() -> BTreeMap<String, Jackpot> {
+    File::open(JACKPOT_FILE_PATH).ok()
+        .and_then(|file| serde_json::from_reader(file).ok())
+        .unwrap_or_default()
+}
+
+// The pools of one machine. A machine played for the first time (or a file that fails to parse)
+// starts every pool at its seed value, with tiers fitted to the machine's layout
+pub fn get_jackpot(name: &str, machine: &SlotMachine) -> Jackpot {
+    if let Some(jackpot) = get_jackpots().remove(name) {
+        return jackpot.with_machine(name);
+    }
+
+    let default_jackpot = Jackpot::for_machine(machine).with_machine(name);
+    let _ = save_jackpot(&default_jackpot);
+    default_jackpot
+}
+
+// Save one machine's pools, leaving the other machines' untouched
+pub fn save_jackpot(jackpot: &Jackpot) -> Result<()> {
+    let mut jackpots = get_jackpots();
+    jackpots.insert(jackpot.machine().to_string(), jackpot.clone());
+    let json = serde_json::to_string_pretty(&jackpots)?;
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(JACKPOT_FILE_PATH)?;
+    file.write_all(json.as_bytes())?;
+    Ok(())
+}
+
+// A machine by preset name (e.g. "5x3") or from a machine definition JSON file
+pub fn load_machine(name_or_path: &str) -> std::result::Result<SlotMachine, String> {
+    if let Some(machine) = SlotMachine::preset(name_or_path) {
+        return Ok(machine);
+    }
+
+    let path = Path::new(name_or_path);
+    if !path.exists() {
+        return Err(format!(
+            "unknown machine: {} (presets: {}, or a path to a machine JSON file)",
+            name_or_path,
+            SlotMachine::PRESETS.join(", ")
+        ));
+    }
+    let file = File::open(path).map_err(|e| format!("can't open {}: {}", name_or_path, e))?;
+    serde_json::from_reader(file).map_err(|e| format!("invalid machine file {}: {}", name_or_path, e))
+}
+
+pub fn save_machine(machine: &SlotMachine, path: &str) -> Result<()> {
+    let json = serde_json::to_string_pretty(machine)?;
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.write_all(json.as_bytes())?;
+    Ok(())
+}
@@ -1 +1,2 @@
-pub mod json;
\ No newline at end of file
+pub mod json;
+pub mod textures;
\ No newline at end of file
@@ -0,0 +1,10 @@
+/// Symbols that have a texture, by name, and the file it's loaded from. Symbols missing here
+/// are drawn with their emoji icon. Kept out of the UI so the headless tools can validate
+/// machines against it without linking the engine
+pub const SYMBOL_TEXTURES: [(&str, &str); 5] = [
+    ("Cherry", "textures/symbols/cherry.png"),
+    ("Lemon", "textures/symbols/lemon.png"),
+    ("Bell", "textures/symbols/bell.png"),
+    ("Star", "textures/symbols/star.png"),
+    ("Seven", "textures/symbols/seven.png"),
+];